@@ -11,20 +11,20 @@ export class BattleManager {
     constructor(
         connection: Connection,
         wallet: anchor.Wallet,
-        program: any,
-        startingBattleId: number
+        program: any
     ) {
         this.provider = new anchor.AnchorProvider(connection, wallet, {
             commitment: BOT_CONFIG.COMMITMENT,
         });
         this.program = program;
-        this.currentBattleId = startingBattleId;
+        this.currentBattleId = 0;
     }
 
     getBattlePDA(battleId: number): PublicKey {
         const [pda] = PublicKey.findProgramAddressSync(
             [
                 Buffer.from("battle"),
+                this.getArenaPDA().toBuffer(),
                 new anchor.BN(battleId).toArrayLike(Buffer, "le", 8)
             ],
             this.program.programId
@@ -37,6 +37,7 @@ export class BattleManager {
         const [pda] = PublicKey.findProgramAddressSync(
             [
                 Buffer.from("market"),
+                this.getArenaPDA().toBuffer(),
                 new anchor.BN(battleId).toArrayLike(Buffer, "le", 8)
             ],
             this.program.programId
@@ -44,39 +45,90 @@ export class BattleManager {
         return pda;
     }
 
-    getGlobalPDA(): PublicKey {
+    getOrderBookPDA(battleId: number): PublicKey {
         const [pda] = PublicKey.findProgramAddressSync(
-            [Buffer.from("global")],
+            [
+                Buffer.from("order_book"),
+                this.getArenaPDA().toBuffer(),
+                new anchor.BN(battleId).toArrayLike(Buffer, "le", 8)
+            ],
             this.program.programId
         );
         return pda;
     }
 
-    async updateCurrentBattle(battleId: number): Promise<void> {
-        try {
-            const globalPDA = this.getGlobalPDA();
+    getOrderQueuePDA(battleId: number): PublicKey {
+        const [pda] = PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("order_queue"),
+                this.getArenaPDA().toBuffer(),
+                new anchor.BN(battleId).toArrayLike(Buffer, "le", 8)
+            ],
+            this.program.programId
+        );
+        return pda;
+    }
 
-            await this.program.methods
-                .updateCurrentBattle(new anchor.BN(battleId))
-                .accounts({
-                    globalState: globalPDA,
-                    authority: this.provider.wallet.publicKey,
-                })
-                .rpc();
+    getLpPositionPDA(battleId: number, owner: PublicKey): PublicKey {
+        const [pda] = PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("lp_position"),
+                this.getArenaPDA().toBuffer(),
+                new anchor.BN(battleId).toArrayLike(Buffer, "le", 8),
+                owner.toBuffer()
+            ],
+            this.program.programId
+        );
+        return pda;
+    }
 
-            console.log(` Updated global state to battle #${battleId}`);
-        } catch (error: any) {
-            console.error(` Failed to update global state:`, error.message);
-        }
+    getPositionPDA(battleId: number, user: PublicKey, creatureIndex: number): PublicKey {
+        const [pda] = PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("position"),
+                this.getArenaPDA().toBuffer(),
+                new anchor.BN(battleId).toArrayLike(Buffer, "le", 8),
+                user.toBuffer(),
+                Buffer.from([creatureIndex])
+            ],
+            this.program.programId
+        );
+        return pda;
+    }
+
+    getArenaPDA(): PublicKey {
+        const [pda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("arena"), Buffer.from(BOT_CONFIG.ARENA_NAME)],
+            this.program.programId
+        );
+        return pda;
+    }
+
+    getTemplatePDA(templateId: anchor.BN): PublicKey {
+        const [pda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("template"), templateId.toArrayLike(Buffer, "le", 8)],
+            this.program.programId
+        );
+        return pda;
+    }
+
+    getGlobalPDA(): PublicKey {
+        const [pda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("global")],
+            this.program.programId
+        );
+        return pda;
     }
 
-    async battleExists(battleId: number): Promise<boolean> {
+    // The live battle the arena points at, if the bot was restarted mid-battle
+    async findLiveBattle(): Promise<number | null> {
+        const arena = await this.program.account.arena.fetch(this.getArenaPDA());
+        const battleId = arena.currentBattleId.toNumber();
         try {
-            const battlePDA = this.getBattlePDA(battleId);
-            await this.program.account.battleState.fetch(battlePDA);
-            return true;
+            const battle = await this.getBattleState(battleId);
+            return battle.isBattleOver ? null : battleId;
         } catch {
-            return false;
+            return null;
         }
     }
 
@@ -87,64 +139,70 @@ export class BattleManager {
     }
 
 
-    async initializeBattle(battleId: number): Promise<string> {
+    // Creates the next battle and its market together from the arena's template;
+    // the id comes from the arena's counter
+    async createRound(): Promise<number> {
+        const arena = await this.program.account.arena.fetch(this.getArenaPDA());
+        const battleId = arena.nextBattleId.toNumber();
         const battlePDA = this.getBattlePDA(battleId);
-
-        console.log(`\nInitializing Battle #${battleId}...`);
-        console.log(`Battle PDA: ${battlePDA.toBase58()}`);
-
-        const tx = await this.program.methods
-            .initializeBattle(
-                new anchor.BN(battleId),
-                BOT_CONFIG.CREATURE_HP,
-                BOT_CONFIG.CREATURE_ATK,
-                BOT_CONFIG.CREATURE_DEF,
-                BOT_CONFIG.CREATURE_SPD,
-                new anchor.BN(BOT_CONFIG.TURN_INTERVAL),
-                new anchor.BN(BOT_CONFIG.MAX_DURATION)
-            )
-            .accounts({
-                battleState: battlePDA,
-                authority: this.provider.wallet.publicKey,
-                systemProgram: anchor.web3.SystemProgram.programId,
-            })
-            .rpc();
-
-        console.log(` Battle initialized! Tx: ${tx}`);
-        return tx;
-    }
-
-    async initializeMarket(battleId: number): Promise<string> {
         const marketPDA = this.getMarketPDA(battleId);
         const initialLiquidity = new anchor.BN(
             BOT_CONFIG.INITIAL_LIQUIDITY_SOL * 1_000_000_000
         );
 
-        console.log(`\nInitializing Market for Battle #${battleId}...`);
+        console.log(`\nCreating Round #${battleId} in arena ${BOT_CONFIG.ARENA_NAME}...`);
+        console.log(`Battle PDA: ${battlePDA.toBase58()}`);
         console.log(`Market PDA: ${marketPDA.toBase58()}`);
         console.log(`Initial Liquidity: ${BOT_CONFIG.INITIAL_LIQUIDITY_SOL} SOL`);
 
         const tx = await this.program.methods
-            .initializeMarket(new anchor.BN(battleId), initialLiquidity)
+            .createRound(initialLiquidity)
             .accounts({
+                globalState: this.getGlobalPDA(),
+                arena: this.getArenaPDA(),
+                template: this.getTemplatePDA(arena.templateId),
+                battleState: battlePDA,
                 marketState: marketPDA,
+                lpPosition: this.getLpPositionPDA(battleId, this.provider.wallet.publicKey),
                 authority: this.provider.wallet.publicKey,
+                liquidityProvider: this.provider.wallet.publicKey,
                 systemProgram: anchor.web3.SystemProgram.programId,
             })
             .rpc();
 
-        console.log(`Market initialized! Tx: ${tx}`);
-        return tx;
+        console.log(` Round created! Tx: ${tx}`);
+        return battleId;
     }
 
 
     async executeTurn(battleId: number): Promise<string> {
         const battlePDA = this.getBattlePDA(battleId);
 
+        // Batch-mode markets clear their queued orders at the start of each turn,
+        // and once the queue exists the program requires it
+        let marketPDA: PublicKey | null = null;
+        let orderQueuePDA: PublicKey | null = null;
+        const queue = this.getOrderQueuePDA(battleId);
+        if (await this.provider.connection.getAccountInfo(queue)) {
+            marketPDA = this.getMarketPDA(battleId);
+            orderQueuePDA = queue;
+        }
+
+        // Resting limit orders crossed since the last trade fill on the turn crank
+        let orderBookPDA: PublicKey | null = null;
+        const book = this.getOrderBookPDA(battleId);
+        if (await this.provider.connection.getAccountInfo(book)) {
+            marketPDA = this.getMarketPDA(battleId);
+            orderBookPDA = book;
+        }
+
         const tx = await this.program.methods
             .executeTurn()
             .accounts({
                 battleState: battlePDA,
+                marketState: marketPDA,
+                orderQueue: orderQueuePDA,
+                orderBook: orderBookPDA,
                 executer: this.provider.wallet.publicKey,
                 clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
             })
@@ -154,6 +212,84 @@ export class BattleManager {
     }
 
 
+    // Delivers every cleared batch fill so the queue has room for the next batch
+    async settleBatchFills(battleId: number): Promise<void> {
+        const queuePDA = this.getOrderQueuePDA(battleId);
+        if (!(await this.provider.connection.getAccountInfo(queuePDA))) return;
+
+        const queue = await this.program.account.orderQueue.fetch(queuePDA);
+        const owners = new Map<string, { user: PublicKey; creatureIndex: number }>();
+        for (const order of queue.orders) {
+            if (!order.isCleared) continue;
+            owners.set(`${order.user.toBase58()}:${order.creatureIndex}`, {
+                user: order.user,
+                creatureIndex: order.creatureIndex,
+            });
+        }
+
+        for (const { user, creatureIndex } of owners.values()) {
+            try {
+                await this.program.methods
+                    .settleBatchOrders()
+                    .accounts({
+                        battleState: this.getBattlePDA(battleId),
+                        marketState: this.getMarketPDA(battleId),
+                        orderQueue: queuePDA,
+                        userPosition: this.getPositionPDA(battleId, user, creatureIndex),
+                        user: user,
+                    })
+                    .rpc();
+            } catch (error: any) {
+                console.error(` Could not settle batch fills for ${user.toBase58()}:`, error.message);
+            }
+        }
+    }
+
+
+    // Takes fully filled limit orders off the book and delivers them to their owners
+    async settleFilledLimitOrders(battleId: number): Promise<void> {
+        const bookPDA = this.getOrderBookPDA(battleId);
+        if (!(await this.provider.connection.getAccountInfo(bookPDA))) return;
+
+        const book = await this.program.account.orderBook.fetch(bookPDA);
+        for (const order of book.orders) {
+            if (!order.remaining.isZero()) continue;
+            try {
+                await this.program.methods
+                    .settleLimitOrder(order.id)
+                    .accounts({
+                        battleState: this.getBattlePDA(battleId),
+                        orderBook: bookPDA,
+                        userPosition: this.getPositionPDA(battleId, order.owner, order.creatureIndex),
+                        user: order.owner,
+                    })
+                    .rpc();
+            } catch (error: any) {
+                console.error(` Could not settle limit order #${order.id.toString()}:`, error.message);
+            }
+        }
+    }
+
+
+    async withdrawLiquidity(battleId: number): Promise<void> {
+        try {
+            const tx = await this.program.methods
+                .lpWithdraw()
+                .accounts({
+                    battleState: this.getBattlePDA(battleId),
+                    marketState: this.getMarketPDA(battleId),
+                    lpPosition: this.getLpPositionPDA(battleId, this.provider.wallet.publicKey),
+                    owner: this.provider.wallet.publicKey,
+                })
+                .rpc();
+
+            console.log(` Withdrew liquidity from battle #${battleId}. Tx: ${tx}`);
+        } catch (error: any) {
+            console.error(` Failed to withdraw liquidity:`, error.message);
+        }
+    }
+
+
     async runBattle(battleId: number): Promise<void> {
         console.log(`\nStarting Battle #${battleId} monitoring...`);
 
@@ -187,6 +323,8 @@ export class BattleManager {
                 console.log(`\nExecuting Turn ${battle.currentTurn.toNumber() + 1}...`);
                 const tx = await this.executeTurn(battleId);
                 console.log(`Turn executed! Tx: ${tx.substring(0, 20)}...`);
+                await this.settleBatchFills(battleId);
+                await this.settleFilledLimitOrders(battleId);
 
                 const updatedBattle = await this.getBattleState(battleId);
                 console.log(`  HP: ${updatedBattle.creatureHp}`);
@@ -211,32 +349,27 @@ export class BattleManager {
     async start(): Promise<void> {
         console.log(`\nBeast Index Arena Bot Started!`);
         console.log(` RPC: ${BOT_CONFIG.RPC_ENDPOINT}`);
-        console.log(` Starting Battle ID: ${this.currentBattleId}`);
         console.log(` Turn Interval: ${BOT_CONFIG.TURN_INTERVAL}s`);
         console.log(`Delay Between Battles: ${BOT_CONFIG.DELAY_BETWEEN_BATTLES_SECONDS}s\n`);
 
         while (true) {
             try {
-                const exists = await this.battleExists(this.currentBattleId);
+                const liveBattle = await this.findLiveBattle();
 
-                if (!exists) {
-                    console.log(`\n Creating Battle #${this.currentBattleId}...`);
-                    await this.initializeBattle(this.currentBattleId);
-                    await this.initializeMarket(this.currentBattleId);
-                    await this.updateCurrentBattle(this.currentBattleId);
+                if (liveBattle === null) {
+                    this.currentBattleId = await this.createRound();
                     console.log(`\n Battle #${this.currentBattleId} ready for bets!`);
                 } else {
-                    console.log(`\n Battle #${this.currentBattleId} already exists`);
-                    await this.updateCurrentBattle(this.currentBattleId);
+                    this.currentBattleId = liveBattle;
+                    console.log(`\n Resuming Battle #${this.currentBattleId}`);
                 }
 
                 await this.runBattle(this.currentBattleId);
+                await this.withdrawLiquidity(this.currentBattleId);
 
                 console.log(`\n Waiting ${BOT_CONFIG.DELAY_BETWEEN_BATTLES_SECONDS}s before next battle...`);
                 await this.sleep(BOT_CONFIG.DELAY_BETWEEN_BATTLES_SECONDS * 1000);
 
-                this.currentBattleId++;
-
             } catch (error: any) {
                 console.error(`\n Error in bot loop:`, error.message);
                 console.log(` Retrying in 10 seconds...`);
@@ -2,6 +2,11 @@ export const BOT_CONFIG = {
     TURN_INTERVAL_SECONDS: 30,
     DELAY_BETWEEN_BATTLES_SECONDS: 60,
 
+    // Arena the bot runs rounds in. initialize-global.ts creates it with a
+    // battle template holding the stats, timing and market settings below;
+    // change them on-chain with update_template
+    ARENA_NAME: "main",
+
     CREATURE_HP: 100,
     CREATURE_ATK: 50,
     CREATURE_DEF: 20,
@@ -9,16 +14,43 @@ export const BOT_CONFIG = {
 
     TURN_INTERVAL: 30,
     MAX_DURATION: 86400,
+    BETTING_WINDOW_SECONDS: 0,
+    // How a timed-out battle is decided: "draw" or "highestHp"
+    TIEBREAK: "draw",
 
 
     INITIAL_LIQUIDITY_SOL: 10,
 
+    // Market lock rules (0 disables a rule)
+    LOCK_AFTER_TURN: 0,
+    LOCK_AT_ALIVE_COUNT: 2,
+    LOCK_BEFORE_END_SECONDS: 0,
+    ALLOW_SELLS_WHEN_LOCKED: true,
+
+    // Queue trades and clear them once per turn
+    BATCH_MODE: false,
+
+    // Exposure limits (0 disables a limit)
+    MAX_BET_SOL: 0,
+    MAX_POSITION_SHARES: 0,
+    MAX_TOTAL_POOL_SOL: 0,
+    MAX_PRICE_IMPACT_BPS: 2000,
+
+    // How long winners and LPs have to claim (0 uses the 30 day default)
+    CLAIM_WINDOW_SECONDS: 0,
+
+    // Protocol config, set once by initialize-global.ts and changed with update_config
+    MIN_BET_SOL: 0.01,
+    PROTOCOL_MAX_BET_SOL: 0,
+    TRADE_FEE_BPS: 0,
+    ABILITY_MULTIPLIERS_BPS: [10_000, 15_000, 7_500],
+    PRICE_SCALE: 1_000_000_000,
+    DEFAULT_CLAIM_WINDOW_SECONDS: 30 * 24 * 60 * 60,
+
 
     RPC_ENDPOINT: "https://api.devnet.solana.com",
     COMMITMENT: "confirmed" as const,
 
 
     PROGRAM_ID: "H3EA4meFoepS9ZvufFg83ZJ3E6Ma98hQAbHhx64A9NoB",
-
-    STARTING_BATTLE_ID: 0,
 };
@@ -65,8 +65,7 @@ async function main() {
     const battleManager = new BattleManager(
         connection,
         wallet,
-        program,
-        BOT_CONFIG.STARTING_BATTLE_ID
+        program
     );
 
     process.on("SIGINT", () => {
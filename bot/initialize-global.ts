@@ -15,6 +15,19 @@ const wallet = new anchor.Wallet(keypair);
 const provider = new anchor.AnchorProvider(connection, wallet, { commitment: BOT_CONFIG.COMMITMENT });
 const program = new Program(IDL as any, provider);
 
+const marketConfig = {
+    lockAfterTurn: new anchor.BN(BOT_CONFIG.LOCK_AFTER_TURN),
+    lockAtAliveCount: BOT_CONFIG.LOCK_AT_ALIVE_COUNT,
+    lockBeforeEndSecs: new anchor.BN(BOT_CONFIG.LOCK_BEFORE_END_SECONDS),
+    allowSellsWhenLocked: BOT_CONFIG.ALLOW_SELLS_WHEN_LOCKED,
+    batchMode: BOT_CONFIG.BATCH_MODE,
+    maxBet: new anchor.BN(BOT_CONFIG.MAX_BET_SOL * anchor.web3.LAMPORTS_PER_SOL),
+    maxPosition: new anchor.BN(BOT_CONFIG.MAX_POSITION_SHARES),
+    maxTotalPool: new anchor.BN(BOT_CONFIG.MAX_TOTAL_POOL_SOL * anchor.web3.LAMPORTS_PER_SOL),
+    maxPriceImpactBps: BOT_CONFIG.MAX_PRICE_IMPACT_BPS,
+    claimWindowSecs: new anchor.BN(BOT_CONFIG.CLAIM_WINDOW_SECONDS),
+};
+
 async function initializeGlobal() {
     const [globalPDA] = anchor.web3.PublicKey.findProgramAddressSync(
         [Buffer.from("global")],
@@ -47,4 +60,131 @@ async function initializeGlobal() {
     }
 }
 
-initializeGlobal().catch(console.error);
+async function initializeConfig() {
+    const [configPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("protocol_config")],
+        program.programId
+    );
+
+    console.log("⚙️  Initializing protocol config...");
+    console.log("   Config PDA:", configPDA.toString());
+
+    const params = {
+        minBet: new anchor.BN(BOT_CONFIG.MIN_BET_SOL * anchor.web3.LAMPORTS_PER_SOL),
+        maxBet: new anchor.BN(BOT_CONFIG.PROTOCOL_MAX_BET_SOL * anchor.web3.LAMPORTS_PER_SOL),
+        tradeFeeBps: BOT_CONFIG.TRADE_FEE_BPS,
+        defaultTurnInterval: new anchor.BN(BOT_CONFIG.TURN_INTERVAL),
+        defaultMaxDuration: new anchor.BN(BOT_CONFIG.MAX_DURATION),
+        abilityMultipliersBps: BOT_CONFIG.ABILITY_MULTIPLIERS_BPS,
+        priceScale: new anchor.BN(BOT_CONFIG.PRICE_SCALE),
+        defaultClaimWindowSecs: new anchor.BN(BOT_CONFIG.DEFAULT_CLAIM_WINDOW_SECONDS),
+    };
+
+    try {
+        const tx = await program.methods
+            .initializeConfig(params)
+            .accounts({
+                protocolConfig: configPDA,
+                authority: keypair.publicKey,
+                systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .rpc();
+
+        console.log("✅ Protocol config initialized!");
+        console.log("   Transaction:", tx);
+    } catch (error: any) {
+        if (error.message.includes("already in use")) {
+            console.log("⚠️  Protocol config already initialized");
+        } else {
+            console.error("❌ Error initializing protocol config:", error);
+            throw error;
+        }
+    }
+}
+
+// Stores the creature stats, abilities, timing and market settings from
+// config.ts as a battle template, and returns its id
+async function createTemplate(globalPDA: anchor.web3.PublicKey): Promise<anchor.BN> {
+    const global = await program.account.globalState.fetch(globalPDA);
+    const templateId: anchor.BN = global.nextTemplateId;
+    const [templatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("template"), templateId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+    );
+
+    console.log(`📋 Creating battle template #${templateId.toString()}...`);
+    console.log("   Template PDA:", templatePDA.toString());
+
+    const params = {
+        creatureHp: Array(4).fill(BOT_CONFIG.CREATURE_HP),
+        creatureAtk: Array(4).fill(BOT_CONFIG.CREATURE_ATK),
+        creatureDef: Array(4).fill(BOT_CONFIG.CREATURE_DEF),
+        creatureSpd: Array(4).fill(BOT_CONFIG.CREATURE_SPD),
+        abilityMultipliersBps: BOT_CONFIG.ABILITY_MULTIPLIERS_BPS,
+        turnInterval: new anchor.BN(BOT_CONFIG.TURN_INTERVAL),
+        maxDuration: new anchor.BN(BOT_CONFIG.MAX_DURATION),
+        bettingWindowSecs: new anchor.BN(BOT_CONFIG.BETTING_WINDOW_SECONDS),
+        tiebreak: { [BOT_CONFIG.TIEBREAK]: {} },
+        marketConfig: marketConfig,
+    };
+
+    const tx = await program.methods
+        .createTemplate(params)
+        .accounts({
+            globalState: globalPDA,
+            template: templatePDA,
+            authority: keypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    console.log("✅ Battle template created!");
+    console.log("   Transaction:", tx);
+    return templateId;
+}
+
+async function createArena() {
+    const [globalPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("global")],
+        program.programId
+    );
+    const [arenaPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("arena"), Buffer.from(BOT_CONFIG.ARENA_NAME)],
+        program.programId
+    );
+
+    if (await connection.getAccountInfo(arenaPDA)) {
+        console.log("⚠️  Arena already created");
+        return;
+    }
+
+    const templateId = await createTemplate(globalPDA);
+    const [templatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("template"), templateId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+    );
+
+    console.log(`🏟️  Creating arena ${BOT_CONFIG.ARENA_NAME}...`);
+    console.log("   Arena PDA:", arenaPDA.toString());
+
+    try {
+        const tx = await program.methods
+            .createArena(BOT_CONFIG.ARENA_NAME, templateId, BOT_CONFIG.TRADE_FEE_BPS)
+            .accounts({
+                globalState: globalPDA,
+                template: templatePDA,
+                arena: arenaPDA,
+                authority: keypair.publicKey,
+                systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .rpc();
+
+        console.log("✅ Arena created!");
+        console.log("   Transaction:", tx);
+    } catch (error: any) {
+        console.error("❌ Error creating arena:", error);
+        throw error;
+    }
+}
+
+initializeGlobal().then(initializeConfig).then(createArena).catch(console.error);
@@ -13,7 +13,7 @@ pub enum GameError {
     #[msg("Invalid creature index (must be 0-3)")]
     InvalidCreatureIndex,
 
-    #[msg("Bet amount too small (minimum 0.01 SOL)")]
+    #[msg("Bet amount is below the minimum bet")]
     BetTooSmall,
 
     #[msg("Cannot bet on a dead creature")]
@@ -38,4 +38,199 @@ pub enum GameError {
 
     #[msg("Insufficient shares to sell")]
     InsufficientShares,
+
+    #[msg("Only the battle authority can do this")]
+    Unauthorized,
+
+    #[msg("Battle is paused")]
+    BattlePaused,
+
+    #[msg("Battle is not paused")]
+    BattleNotPaused,
+
+    #[msg("Battle was cancelled")]
+    BattleCancelled,
+
+    #[msg("Market is not in refund mode")]
+    NotInRefundMode,
+
+    #[msg("Start time cannot be in the past")]
+    InvalidStartTime,
+
+    #[msg("Battle has not started yet - betting window is still open")]
+    BattleNotStarted,
+
+    #[msg("Market is locked for new bets")]
+    BetsLocked,
+
+    #[msg("Market is locked for sells")]
+    SellsLocked,
+
+    #[msg("Market trades in batches - queue the order instead")]
+    BatchModeActive,
+
+    #[msg("Market does not trade in batches")]
+    BatchModeDisabled,
+
+    #[msg("Order queue is full - wait for the next turn")]
+    OrderQueueFull,
+
+    #[msg("No matching orders in the queue")]
+    NoPendingOrders,
+
+    #[msg("Market does not hold enough lamports to cover what it owes")]
+    MarketInsolvent,
+
+    #[msg("No LP shares to redeem")]
+    NoLpShares,
+
+    #[msg("Liquidity can only be added before the first bet or turn")]
+    LiquidityWindowClosed,
+
+    #[msg("House vault doesn't have enough idle lamports")]
+    InsufficientVaultLiquidity,
+
+    #[msg("Token market needs the mint, token accounts and token program")]
+    MissingTokenAccounts,
+
+    #[msg("Token account or mint doesn't match the market's mint")]
+    WrongMint,
+
+    #[msg("Not supported for token-denominated markets")]
+    UnsupportedForTokenMarket,
+
+    #[msg("Shares in this market are outcome tokens - use the outcome token instructions")]
+    SharesAreTokenized,
+
+    #[msg("Market does not use outcome tokens")]
+    SharesNotTokenized,
+
+    #[msg("Market already has trades")]
+    MarketAlreadyTrading,
+
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+
+    #[msg("Cannot transfer a position to yourself")]
+    SelfTransfer,
+
+    #[msg("Source and destination creature must differ")]
+    SameCreature,
+
+    #[msg("Received fewer shares than the minimum requested")]
+    SlippageExceeded,
+
+    #[msg("Basket needs at least one weighted creature")]
+    EmptyBasket,
+
+    #[msg("Missing position account for a weighted creature")]
+    MissingPositionAccount,
+
+    #[msg("Betting on this exotic market or round is closed")]
+    ExoticBettingClosed,
+
+    #[msg("Invalid selection for this market")]
+    InvalidSelection,
+
+    #[msg("This round has not been settled yet")]
+    RoundNotSettled,
+
+    #[msg("Order book is full")]
+    OrderBookFull,
+
+    #[msg("Bet exceeds the market's maximum bet size")]
+    BetTooLarge,
+
+    #[msg("Position would exceed the market's per-user limit for this creature")]
+    PositionLimitExceeded,
+
+    #[msg("Market's total pool limit reached")]
+    PoolLimitExceeded,
+
+    #[msg("Trade moves the price more than the market allows")]
+    PriceImpactTooHigh,
+
+    #[msg("Claim window has expired")]
+    ClaimWindowExpired,
+
+    #[msg("Grace period after settlement has not passed yet")]
+    GracePeriodNotOver,
+
+    #[msg("Position still holds unclaimed shares")]
+    PositionStillOpen,
+
+    #[msg("Market must be closed first")]
+    MarketNotClosed,
+
+    #[msg("Market config doesn't fit the battle")]
+    InvalidMarketConfig,
+
+    #[msg("Signer doesn't hold the required role")]
+    MissingRole,
+
+    #[msg("Invalid role member")]
+    InvalidRoleMember,
+
+    #[msg("No free role slots")]
+    RoleListFull,
+
+    #[msg("Member doesn't hold that role")]
+    RoleNotHeld,
+
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+
+    #[msg("Invalid protocol config")]
+    InvalidProtocolConfig,
+
+    #[msg("Trading is paused protocol-wide")]
+    TradingPaused,
+
+    #[msg("Turns are paused protocol-wide")]
+    TurnsPaused,
+
+    #[msg("Claims are paused protocol-wide")]
+    ClaimsPaused,
+
+    #[msg("Arena name must be 1 to 32 bytes")]
+    InvalidArenaName,
+
+    #[msg("Invalid arena config")]
+    InvalidArenaConfig,
+
+    #[msg("Invalid battle template")]
+    InvalidTemplate,
+
+    #[msg("Template is not the one this arena uses")]
+    TemplateMismatch,
+
+    #[msg("This battle's order queue has to be passed in")]
+    MissingOrderQueue,
+
+    #[msg("Order is worth less than the minimum bet")]
+    OrderTooSmall,
+
+    #[msg("This battle's order book has to be passed in")]
+    MissingOrderBook,
+
+    #[msg("Order is still open")]
+    OrderStillOpen,
+
+    #[msg("House vault capital is still deployed in markets")]
+    VaultCapitalDeployed,
+
+    #[msg("The battle's order queue and order book must be closed first")]
+    OrdersNotClosed,
+
+    #[msg("LP shares are still outstanding")]
+    LpSharesOutstanding,
+
+    #[msg("The battle's exotic markets must be closed first")]
+    ExoticMarketsOpen,
+
+    #[msg("Deposit is too small to buy any shares")]
+    ZeroShares,
 }
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use crate::state::ProtocolParams;
+
+#[event]
+pub struct PositionSwapped {
+    pub user: Pubkey,
+    pub battle_id: u64,
+    pub from_creature: u8,
+    pub to_creature: u8,
+    pub shares_sold: u64,
+    pub amount: u64,
+    pub shares_bought: u64,
+}
+
+#[event]
+pub struct ProtocolConfigUpdated {
+    pub authority: Pubkey,
+    pub version: u32,
+    pub old_params: ProtocolParams,
+    pub new_params: ProtocolParams,
+}
@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::state::{BattleState, MarketState, OrderBook, UserPosition};
+use crate::errors::GameError;
+use crate::utils::{fill_limit_orders, get_lock_status};
+
+/// Token accounts a trade needs when its market is priced in an SPL token.
+pub struct MarketTokenAccounts<'a, 'info> {
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub user_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub market_vault: &'a InterfaceAccount<'info, TokenAccount>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'a, 'info> MarketTokenAccounts<'a, 'info> {
+    /// Returns `None` for SOL markets. Token markets must pass every account,
+    /// and the mint and user account must match the market's mint.
+    pub fn load(
+        market: &MarketState,
+        mint: &'a Option<InterfaceAccount<'info, Mint>>,
+        user_token_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+        market_vault: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+        token_program: &'a Option<Interface<'info, TokenInterface>>,
+    ) -> Result<Option<Self>> {
+        if market.token_mint == Pubkey::default() {
+            return Ok(None);
+        }
+
+        let (Some(mint), Some(user_token_account), Some(market_vault), Some(token_program)) =
+            (mint, user_token_account, market_vault, token_program)
+        else {
+            return Err(GameError::MissingTokenAccounts.into());
+        };
+        require_keys_eq!(mint.key(), market.token_mint, GameError::WrongMint);
+        require_keys_eq!(user_token_account.mint, market.token_mint, GameError::WrongMint);
+
+        Ok(Some(Self {
+            mint,
+            user_token_account,
+            market_vault,
+            token_program,
+        }))
+    }
+}
+
+/// Moves `amount` from the user into the market, as lamports or tokens.
+pub fn collect_payment<'info>(
+    market: &Account<'info, MarketState>,
+    user: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    tokens: Option<&MarketTokenAccounts<'_, 'info>>,
+    amount: u64,
+) -> Result<()> {
+    match tokens {
+        None => {
+            let cpi_context = CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: user.to_account_info(),
+                    to: market.to_account_info(),
+                },
+            );
+            transfer(cpi_context, amount)
+        }
+        Some(tokens) => {
+            let cpi_context = CpiContext::new(
+                tokens.token_program.to_account_info(),
+                TransferChecked {
+                    from: tokens.user_token_account.to_account_info(),
+                    mint: tokens.mint.to_account_info(),
+                    to: tokens.market_vault.to_account_info(),
+                    authority: user.to_account_info(),
+                },
+            );
+            transfer_checked(cpi_context, amount, tokens.mint.decimals)
+        }
+    }
+}
+
+/// Pays `amount` out of the market, as lamports to `recipient` or tokens to
+/// the user's token account.
+pub fn send_payout<'info>(
+    market: &Account<'info, MarketState>,
+    recipient: &AccountInfo<'info>,
+    tokens: Option<&MarketTokenAccounts<'_, 'info>>,
+    amount: u64,
+) -> Result<()> {
+    match tokens {
+        None => {
+            **market.to_account_info().try_borrow_mut_lamports()? -= amount;
+            **recipient.try_borrow_mut_lamports()? += amount;
+            Ok(())
+        }
+        Some(tokens) => {
+            let battle_id_bytes = market.battle_id.to_le_bytes();
+            let signer_seeds: &[&[&[u8]]] = &[&[b"market", market.arena.as_ref(), battle_id_bytes.as_ref(), &[market.bump]]];
+            let cpi_context = CpiContext::new_with_signer(
+                tokens.token_program.to_account_info(),
+                TransferChecked {
+                    from: tokens.market_vault.to_account_info(),
+                    mint: tokens.mint.to_account_info(),
+                    to: tokens.user_token_account.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            );
+            transfer_checked(cpi_context, amount, tokens.mint.decimals)
+        }
+    }
+}
+
+/// What the market can pay out: lamports above rent for SOL markets, the
+/// vault balance for token markets.
+pub fn available_funds(
+    market: &Account<'_, MarketState>,
+    tokens: Option<&MarketTokenAccounts<'_, '_>>,
+) -> Result<u64> {
+    match tokens {
+        None => {
+            let market_info = market.to_account_info();
+            let rent_exempt = Rent::get()?.minimum_balance(market_info.data_len());
+            Ok(market_info.lamports().saturating_sub(rent_exempt))
+        }
+        Some(tokens) => Ok(tokens.market_vault.amount),
+    }
+}
+
+/// Fills whatever limit orders the current prices cross and moves the
+/// matching lamports between the book's escrow and the market.
+pub fn crank_order_book<'info>(
+    market: &mut Account<'info, MarketState>,
+    book: &mut Account<'info, OrderBook>,
+    battle: &BattleState,
+    now: i64,
+) -> Result<()> {
+    if battle.is_battle_over || battle.is_paused {
+        return Ok(());
+    }
+
+    let lock_status = get_lock_status(&market.config, battle, now);
+    let (lamports_in, lamports_out) = fill_limit_orders(market, book, battle, &lock_status)?;
+
+    let market_info = market.to_account_info();
+    let book_info = book.to_account_info();
+    **book_info.try_borrow_mut_lamports()? -= lamports_in;
+    **market_info.try_borrow_mut_lamports()? += lamports_in;
+    **market_info.try_borrow_mut_lamports()? -= lamports_out;
+    **book_info.try_borrow_mut_lamports()? += lamports_out;
+    Ok(())
+}
+
+/// Cranks the book after a trade moved prices. Once the battle has a book,
+/// leaving it out is an error so no trade can step around resting orders.
+pub fn crank_after_trade<'info>(
+    market: &mut Account<'info, MarketState>,
+    book: Option<&mut Account<'info, OrderBook>>,
+    battle: &BattleState,
+    now: i64,
+) -> Result<()> {
+    match book {
+        Some(book) => crank_order_book(market, book, battle, now),
+        None => {
+            require!(!battle.has_order_book, GameError::MissingOrderBook);
+            Ok(())
+        }
+    }
+}
+
+/// Takes an order off the book: its fills go to the owner (shares onto the
+/// position, lamports to the wallet) and the unfilled escrow goes back.
+/// Returns `(lamports_returned, shares_returned)`.
+pub fn close_limit_order<'info>(
+    book: &mut Account<'info, OrderBook>,
+    index: usize,
+    position: &mut UserPosition,
+    owner: &AccountInfo<'info>,
+) -> Result<(u64, u64)> {
+    let order = book.orders[index];
+    require!(
+        order.owner == owner.key() && order.creature_index == position.creature_index,
+        GameError::InvalidCreatureIndex
+    );
+
+    let (lamports_returned, shares_returned) = if order.is_buy {
+        (order.remaining, order.filled)
+    } else {
+        (order.filled, order.remaining)
+    };
+
+    if shares_returned > 0 {
+        position.deliver_shares(shares_returned, order.cost_basis)?;
+    }
+
+    book.orders.remove(index);
+
+    **book.to_account_info().try_borrow_mut_lamports()? -= lamports_returned;
+    **owner.try_borrow_mut_lamports()? += lamports_returned;
+
+    Ok((lamports_returned, shares_returned))
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::state::GlobalState;
+use crate::errors::GameError;
+
+pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.authority = ctx.accounts.new_authority.key();
+    global_state.pending_authority = Pubkey::default();
+    msg!("Global authority is now {}", global_state.authority);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.pending_authority == new_authority.key() @ GameError::Unauthorized,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub new_authority: Signer<'info>,
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+use crate::state::BattleState;
+use crate::errors::GameError;
+
+pub fn accept_battle_authority(ctx: Context<AcceptBattleAuthority>) -> Result<()> {
+    let battle = &mut ctx.accounts.battle_state;
+    battle.authority = ctx.accounts.new_authority.key();
+    battle.pending_authority = Pubkey::default();
+
+    msg!("Battle {} authority is now {}", battle.battle_id, battle.authority);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptBattleAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        constraint = battle_state.pending_authority == new_authority.key() @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    pub new_authority: Signer<'info>,
+}
@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{BattleState, GlobalState, LpPosition, MarketState, OrderBook};
+use crate::errors::GameError;
+use crate::funds::{available_funds, collect_payment, crank_after_trade, MarketTokenAccounts};
+use crate::utils::{add_market_liquidity, get_lp_value};
+
+pub fn add_liquidity(ctx: Context<AddLiquidity>, amount: u64) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let market = &mut ctx.accounts.market_state;
+    let lp_position = &mut ctx.accounts.lp_position;
+
+    require!(amount > 0, GameError::BetTooSmall);
+    require!(
+        !battle.is_battle_over && battle.current_turn == 0,
+        GameError::LiquidityWindowClosed
+    );
+
+    let tokens = MarketTokenAccounts::load(
+        market,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.provider_token_account,
+        &ctx.accounts.market_vault,
+        &ctx.accounts.token_program,
+    )?;
+    // Priced before the deposit lands, so it buys in at what's already there.
+    let lp_value = get_lp_value(market, available_funds(market, tokens.as_ref())?);
+    collect_payment(
+        market,
+        &ctx.accounts.provider,
+        &ctx.accounts.system_program,
+        tokens.as_ref(),
+        amount,
+    )?;
+
+    let lp_shares = add_market_liquidity(market, amount, lp_value)?;
+
+    if lp_position.owner == Pubkey::default() {
+        lp_position.owner = ctx.accounts.provider.key();
+        lp_position.battle_id = battle.battle_id;
+        lp_position.arena = battle.arena;
+        lp_position.bump = ctx.bumps.lp_position;
+    }
+    lp_position.shares = lp_position
+        .shares
+        .checked_add(lp_shares)
+        .ok_or(GameError::CalculationOverflow)?;
+    lp_position.deposited = lp_position
+        .deposited
+        .checked_add(amount)
+        .ok_or(GameError::CalculationOverflow)?;
+
+    msg!(
+        "{} added {} lamports of liquidity for {} LP shares",
+        ctx.accounts.provider.key(),
+        amount,
+        lp_shares
+    );
+
+    crank_after_trade(market, ctx.accounts.order_book.as_mut(), battle, Clock::get()?.unix_timestamp)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    /// Deepening the curves moves prices, so crossed limit orders fill right
+    /// after. Required once the battle has a book.
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Option<Account<'info, OrderBook>>,
+
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = LpPosition::LEN,
+        seeds = [
+            b"lp_position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            provider.key().as_ref()
+        ],
+        bump
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub provider_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
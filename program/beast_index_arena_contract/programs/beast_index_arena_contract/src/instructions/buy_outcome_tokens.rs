@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
+use crate::state::{BattleState, GlobalState, MarketState, OrderBook, ProtocolConfig};
+use crate::errors::GameError;
+use crate::funds::{collect_payment, crank_after_trade, MarketTokenAccounts};
+use crate::utils::{calculate_buy_shares, check_buy_limits, get_lock_reason};
+
+/// Tokenized counterpart of `place_bet`: the shares are minted to the user
+/// instead of being booked in a `UserPosition`.
+pub fn buy_outcome_tokens(
+    ctx: Context<BuyOutcomeTokens>,
+    creature_index: u8,
+    amount: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market_state;
+    let battle = &ctx.accounts.battle_state;
+
+    require!(creature_index < 4, GameError::InvalidCreatureIndex);
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    require!(market.tokenized_shares, GameError::SharesNotTokenized);
+    ctx.accounts.protocol_config.check_bet(amount)?;
+    require!(
+        battle.is_alive[creature_index as usize],
+        GameError::CreatureIsDead
+    );
+
+    let clock = Clock::get()?;
+    if let Some(reason) = get_lock_reason(&market.config, battle, clock.unix_timestamp) {
+        msg!("Bets are locked: {:?}", reason);
+        return Err(GameError::BetsLocked.into());
+    }
+
+    let current_shares = market.creature_shares(creature_index)?;
+    let (net_amount, _) = market.split_trade_fee(amount)?;
+    let shares_bought = calculate_buy_shares(current_shares, net_amount, market.k_constant)?;
+    // Outcome tokens move freely between wallets, so `max_position` can't be
+    // enforced per holder; tokenized markets rely on `max_bet` and
+    // `max_total_pool` instead.
+    check_buy_limits(market, amount, current_shares, shares_bought, 0)?;
+
+    let tokens = MarketTokenAccounts::load(
+        market,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.market_vault,
+        &ctx.accounts.token_program,
+    )?;
+    collect_payment(
+        market,
+        &ctx.accounts.user,
+        &ctx.accounts.system_program,
+        tokens.as_ref(),
+        amount,
+    )?;
+
+    market.record_buy(creature_index, net_amount, shares_bought)?;
+
+    let battle_id_bytes = market.battle_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[b"market", market.arena.as_ref(), battle_id_bytes.as_ref(), &[market.bump]]];
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.outcome_token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.outcome_mint.to_account_info(),
+                to: ctx.accounts.user_outcome_account.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        shares_bought,
+    )?;
+
+    msg!(
+        "{} minted {} Creature {} outcome tokens for {}",
+        ctx.accounts.user.key(),
+        shares_bought,
+        creature_index,
+        amount
+    );
+
+    crank_after_trade(market, ctx.accounts.order_book.as_mut(), battle, clock.unix_timestamp)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(creature_index: u8)]
+pub struct BuyOutcomeTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    /// Resting limit orders crossed by this trade fill right after it.
+    /// Required once the battle has a book.
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Option<Account<'info, OrderBook>>,
+
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[creature_index]],
+        bump,
+        mint::token_program = outcome_token_program,
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = outcome_mint,
+        token::authority = user,
+        token::token_program = outcome_token_program,
+    )]
+    pub user_outcome_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub outcome_token_program: Interface<'info, TokenInterface>,
+
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
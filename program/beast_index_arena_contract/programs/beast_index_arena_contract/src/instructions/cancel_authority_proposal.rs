@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::GlobalState;
+use crate::errors::GameError;
+
+pub fn cancel_authority_proposal(ctx: Context<CancelAuthorityProposal>) -> Result<()> {
+    let global_state = &mut ctx.accounts.global_state;
+    require!(
+        global_state.pending_authority != Pubkey::default(),
+        GameError::NoPendingAuthority
+    );
+
+    global_state.pending_authority = Pubkey::default();
+    msg!("Global authority transfer cancelled");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelAuthorityProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, OrderQueue, UserPosition};
+use crate::errors::GameError;
+
+/// Pulls the signer's uncleared orders for one creature out of the queue and
+/// returns the escrowed lamports and shares.
+pub fn cancel_batch_orders(ctx: Context<CancelBatchOrders>) -> Result<()> {
+    let queue = &mut ctx.accounts.order_queue;
+    let position = &mut ctx.accounts.user_position;
+    let user = &ctx.accounts.user;
+
+    let mut lamports_returned: u64 = 0;
+    let mut shares_returned: u64 = 0;
+    let mut shares_cost: u64 = 0;
+    let mut cancelled = 0;
+
+    queue.orders.retain(|order| {
+        let is_mine = order.user == user.key()
+            && order.creature_index == position.creature_index
+            && !order.is_cleared;
+        if is_mine {
+            if order.is_buy {
+                lamports_returned += order.amount;
+            } else {
+                shares_returned += order.amount;
+                shares_cost += order.cost_basis;
+            }
+            cancelled += 1;
+        }
+        !is_mine
+    });
+
+    require!(cancelled > 0, GameError::NoPendingOrders);
+
+    **queue.to_account_info().try_borrow_mut_lamports()? -= lamports_returned;
+    **user.to_account_info().try_borrow_mut_lamports()? += lamports_returned;
+
+    if shares_returned > 0 {
+        position.deliver_shares(shares_returned, shares_cost)?;
+    }
+
+    msg!(
+        "{} cancelled {} orders on Creature {}: {} lamports and {} shares returned",
+        user.key(),
+        cancelled,
+        position.creature_index,
+        lamports_returned,
+        shares_returned
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelBatchOrders<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"order_queue", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_queue.bump,
+    )]
+    pub order_queue: Account<'info, OrderQueue>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[user_position.creature_index]
+        ],
+        bump = user_position.bump,
+        has_one = user,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
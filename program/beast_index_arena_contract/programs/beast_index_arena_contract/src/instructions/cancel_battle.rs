@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, MarketState, Role};
+use crate::errors::GameError;
+
+/// Ends a battle without a winner and puts its market in refund mode. Either
+/// the battle's authority or a pauser can cancel it, so an emergency doesn't
+/// wait on the key that created the battle.
+pub fn cancel_battle(ctx: Context<CancelBattle>) -> Result<()> {
+    let battle = &mut ctx.accounts.battle_state;
+    let market = &mut ctx.accounts.market_state;
+
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+
+    battle.is_cancelled = true;
+    battle.is_paused = false;
+    battle.is_battle_over = true;
+    battle.winner = None;
+    battle.ended_at = Clock::get()?.unix_timestamp;
+    battle.claims_pause_at_end = ctx.accounts.global_state.claims_paused_total(battle.ended_at);
+
+    market.refund_mode = true;
+
+    msg!("Battle {} cancelled, market is refunding", battle.battle_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelBattle<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        constraint = battle_state.authority == authority.key()
+            || global_state.has_role(&authority.key(), Role::Pauser) @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::BattleState;
+use crate::errors::GameError;
+
+pub fn cancel_battle_authority_proposal(ctx: Context<CancelBattleAuthorityProposal>) -> Result<()> {
+    let battle = &mut ctx.accounts.battle_state;
+    require!(
+        battle.pending_authority != Pubkey::default(),
+        GameError::NoPendingAuthority
+    );
+
+    battle.pending_authority = Pubkey::default();
+    msg!("Battle {} authority transfer cancelled", battle.battle_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelBattleAuthorityProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, OrderBook, UserPosition};
+use crate::errors::GameError;
+use crate::funds::close_limit_order;
+
+/// Closes one of the signer's orders: fills are delivered (shares to the
+/// position, lamports to the wallet) and the unfilled escrow is returned.
+/// Works after settlement too; shares landing on a claimed position reopen
+/// it so they can still be claimed.
+pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>, order_id: u64) -> Result<()> {
+    let book = &mut ctx.accounts.order_book;
+    let user = &ctx.accounts.user;
+
+    let index = book
+        .orders
+        .iter()
+        .position(|order| order.id == order_id && order.owner == user.key())
+        .ok_or(GameError::NoPendingOrders)?;
+    let (lamports_returned, shares_returned) = close_limit_order(
+        book,
+        index,
+        &mut ctx.accounts.user_position,
+        &user.to_account_info(),
+    )?;
+
+    msg!(
+        "{} closed limit order #{}: {} lamports and {} shares returned",
+        user.key(),
+        order_id,
+        lamports_returned,
+        shares_returned
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[user_position.creature_index]
+        ],
+        bump = user_position.bump,
+        has_one = user,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, ExoticMarket, ExoticTicket, GlobalState};
+use crate::errors::GameError;
+
+/// Pays a ticket from a settled round: its share of the round's pool if it
+/// picked the winning outcome, or its stake back if the round refunds.
+/// Claims close after the claim window the market was opened with.
+pub fn claim_exotic_winnings(ctx: Context<ClaimExoticWinnings>) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let now = Clock::get()?.unix_timestamp;
+    let exotic = &ctx.accounts.exotic_market;
+    let ticket = &mut ctx.accounts.ticket;
+
+    require!(ticket.round < exotic.settled_rounds, GameError::RoundNotSettled);
+    require!(!ticket.claimed, GameError::AlreadyClaimed);
+    // Rolling rounds settle mid-battle; the window only starts once it ends.
+    require!(
+        !battle.is_battle_over
+            || now <= battle.claim_deadline(exotic.claim_window_secs, &ctx.accounts.global_state, now),
+        GameError::ClaimWindowExpired
+    );
+
+    let payout = match exotic.winning_outcomes[ticket.round as usize] {
+        None => ticket.amount,
+        Some(outcome) => {
+            require!(ticket.outcome == outcome, GameError::NotAWinner);
+            let winning_pool = exotic.pools[exotic.pool_index(ticket.round, outcome)?];
+            (ticket.amount as u128)
+                .checked_mul(exotic.round_totals[ticket.round as usize] as u128)
+                .ok_or(GameError::CalculationOverflow)?
+                .checked_div(winning_pool as u128)
+                .ok_or(GameError::DivisionByZero)? as u64
+        }
+    };
+
+    **exotic.to_account_info().try_borrow_mut_lamports()? -= payout;
+    **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += payout;
+    ticket.claimed = true;
+
+    msg!("{} claimed {} lamports", ctx.accounts.user.key(), payout);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimExoticWinnings<'info> {
+    #[account(
+        seeds = [b"battle", ticket.arena.as_ref(), ticket.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        mut,
+        seeds = [b"exotic", ticket.arena.as_ref(), ticket.battle_id.to_le_bytes().as_ref(), &[ticket.kind as u8]],
+        bump = exotic_market.bump,
+    )]
+    pub exotic_market: Account<'info, ExoticMarket>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(mut, has_one = user)]
+    pub ticket: Account<'info, ExoticTicket>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
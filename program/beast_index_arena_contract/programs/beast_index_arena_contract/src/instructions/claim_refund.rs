@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{BattleState, GlobalState, MarketState, UserPosition};
+use crate::errors::GameError;
+use crate::funds::{send_payout, MarketTokenAccounts};
+use crate::utils::get_position_refund;
+
+pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let now = Clock::get()?.unix_timestamp;
+    let market = &mut ctx.accounts.market_state;
+    let position = &mut ctx.accounts.user_position;
+    let user = &ctx.accounts.user;
+
+    require!(market.is_refunding(battle), GameError::NotInRefundMode);
+    require!(!position.claimed, GameError::AlreadyClaimed);
+    require!(
+        now <= battle.claim_deadline(market.config.claim_window_secs, &ctx.accounts.global_state, now),
+        GameError::ClaimWindowExpired
+    );
+
+    // Each position gets back what it put into the curve, or its share of the
+    // creature's pool if that no longer covers everyone.
+    let creature_index = position.creature_index;
+    let refund = get_position_refund(market, creature_index, position.cost_basis)?;
+
+    let tokens = MarketTokenAccounts::load(
+        market,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.market_vault,
+        &ctx.accounts.token_program,
+    )?;
+    send_payout(market, &user.to_account_info(), tokens.as_ref(), refund)?;
+
+    market.record_sell(creature_index, refund, position.amount)?;
+    market.remove_cost_basis(creature_index, position.cost_basis)?;
+    position.claimed = true;
+
+    msg!(
+        "{} refunded {} lamports for {} shares of Creature {}",
+        user.key(),
+        refund,
+        position.amount,
+        creature_index
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[user_position.creature_index]
+        ],
+        bump = user_position.bump,
+        has_one = user,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
-use crate::state::{BattleState, MarketState, UserPosition};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{BattleState, GlobalState, MarketState, UserPosition};
 use crate::errors::GameError;
+use crate::funds::{available_funds, send_payout, MarketTokenAccounts};
 
 pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
     let battle = &ctx.accounts.battle_state;
+    let now = Clock::get()?.unix_timestamp;
     let market = &mut ctx.accounts.market_state;
     let position = &mut ctx.accounts.user_position;
     let user = &ctx.accounts.user;
@@ -14,6 +17,10 @@ pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
         GameError::NotAWinner
     );
     require!(!position.claimed, GameError::AlreadyClaimed);
+    require!(
+        now <= battle.claim_deadline(market.config.claim_window_secs, &ctx.accounts.global_state, now),
+        GameError::ClaimWindowExpired
+    );
     let winning_pool = match winner {
         0 => market.creature_0_pool,
         1 => market.creature_1_pool,
@@ -28,9 +35,24 @@ pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
         .checked_div(winning_pool as u128)
         .ok_or(GameError::DivisionByZero)? as u64;
 
-    **market.to_account_info().try_borrow_mut_lamports()? -= payout;
-    **user.to_account_info().try_borrow_mut_lamports()? += payout;
+    let tokens = MarketTokenAccounts::load(
+        market,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.market_vault,
+        &ctx.accounts.token_program,
+    )?;
+    require!(
+        available_funds(market, tokens.as_ref())? >= payout,
+        GameError::MarketInsolvent
+    );
+
+    send_payout(market, &user.to_account_info(), tokens.as_ref(), payout)?;
 
+    market.winning_shares_claimed = market
+        .winning_shares_claimed
+        .checked_add(position.amount)
+        .ok_or(GameError::CalculationOverflow)?;
     position.claimed = true;
     Ok(())
 }
@@ -38,14 +60,21 @@ pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
 #[derive(Accounts)]
 pub struct ClaimWinnings<'info> {
     #[account(
-        seeds = [b"battle", battle_state.battle_id.to_le_bytes().as_ref()],
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
         bump = battle_state.bump,
     )]
     pub battle_state: Account<'info, BattleState>,
 
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
     #[account(
         mut,
-        seeds = [b"market", battle_state.battle_id.to_le_bytes().as_ref()],
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
         bump = market_state.bump,
     )]
     pub market_state: Account<'info, MarketState>,
@@ -53,6 +82,7 @@ pub struct ClaimWinnings<'info> {
         mut,
         seeds = [
             b"position",
+            battle_state.arena.as_ref(),
             battle_state.battle_id.to_le_bytes().as_ref(),
             user.key().as_ref(),
             &[user_position.creature_index]
@@ -61,6 +91,21 @@ pub struct ClaimWinnings<'info> {
         has_one = user,
     )]
     pub user_position: Account<'info, UserPosition>,
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 
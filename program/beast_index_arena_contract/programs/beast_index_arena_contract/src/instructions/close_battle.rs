@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, Role};
+use crate::errors::GameError;
+
+/// Closes a finished battle and sweeps its rent to the treasury. Its market,
+/// order queue, order book and exotic markets, if it ever had them, have to be
+/// closed first.
+pub fn close_battle(ctx: Context<CloseBattle>) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+
+    require!(battle.is_battle_over, GameError::BattleNotOver);
+    require!(
+        ctx.accounts.market_state.data_is_empty(),
+        GameError::MarketNotClosed
+    );
+    require!(
+        ctx.accounts.order_queue.data_is_empty() && ctx.accounts.order_book.data_is_empty(),
+        GameError::OrdersNotClosed
+    );
+    require!(battle.open_exotic_markets == 0, GameError::ExoticMarketsOpen);
+
+    msg!("Battle {} closed", battle.battle_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseBattle<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        has_one = treasury,
+        constraint = global_state.has_role(&authority.key(), Role::TreasuryManager) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        close = treasury,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    /// CHECK: Only checked to be empty, i.e. never created or already closed.
+    #[account(
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_state: UncheckedAccount<'info>,
+
+    /// CHECK: Only checked to be empty, like the market.
+    #[account(
+        seeds = [b"order_queue", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub order_queue: UncheckedAccount<'info>,
+
+    /// CHECK: Only checked to be empty, like the market.
+    #[account(
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub order_book: UncheckedAccount<'info>,
+
+    /// CHECK: Must match `global_state.treasury`; only receives lamports.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
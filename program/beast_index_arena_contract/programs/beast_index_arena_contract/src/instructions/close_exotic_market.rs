@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, ExoticMarket, GlobalState, Role};
+use crate::errors::GameError;
+
+/// Closes an exotic market once its claim window has passed, sweeping unclaimed
+/// stakes and the rent to the treasury. Its battle can't be closed before it.
+pub fn close_exotic_market(ctx: Context<CloseExoticMarket>) -> Result<()> {
+    let battle = &mut ctx.accounts.battle_state;
+    let now = Clock::get()?.unix_timestamp;
+    let exotic = &ctx.accounts.exotic_market;
+
+    require!(battle.is_battle_over, GameError::BattleNotOver);
+    require!(
+        now > battle.claim_deadline(exotic.claim_window_secs, &ctx.accounts.global_state, now),
+        GameError::GracePeriodNotOver
+    );
+
+    battle.open_exotic_markets -= 1;
+
+    msg!(
+        "{:?} market for battle {} closed, {} lamports swept to the treasury",
+        exotic.kind,
+        battle.battle_id,
+        exotic.to_account_info().lamports()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseExoticMarket<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        has_one = treasury,
+        constraint = global_state.has_role(&authority.key(), Role::TreasuryManager) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        mut,
+        seeds = [b"exotic", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[exotic_market.kind as u8]],
+        bump = exotic_market.bump,
+        close = treasury,
+    )]
+    pub exotic_market: Account<'info, ExoticMarket>,
+
+    /// CHECK: Must match `global_state.treasury`; only receives lamports.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::state::{ExoticMarket, ExoticTicket};
+use crate::errors::GameError;
+
+/// Closes a ticket with nothing left to claim (claimed, on a settled round it
+/// lost, or in a closed market) and returns its rent to the owner.
+pub fn close_exotic_ticket(ctx: Context<CloseExoticTicket>) -> Result<()> {
+    let ticket = &ctx.accounts.ticket;
+
+    let exotic_info = &ctx.accounts.exotic_market;
+    let mut closable = ticket.claimed || exotic_info.data_is_empty();
+    if !closable {
+        let exotic = ExoticMarket::try_deserialize(&mut &exotic_info.try_borrow_data()?[..])?;
+        closable = ticket.round < exotic.settled_rounds
+            && exotic.winning_outcomes[ticket.round as usize]
+                .is_some_and(|outcome| outcome != ticket.outcome);
+    }
+    require!(closable, GameError::PositionStillOpen);
+
+    msg!(
+        "{} closed {:?} ticket in battle {}",
+        ctx.accounts.user.key(),
+        ticket.kind,
+        ticket.battle_id
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseExoticTicket<'info> {
+    #[account(mut, has_one = user, close = user)]
+    pub ticket: Account<'info, ExoticTicket>,
+
+    /// CHECK: Only read if it exists, to see whether the ticket lost.
+    #[account(
+        seeds = [b"exotic", ticket.arena.as_ref(), ticket.battle_id.to_le_bytes().as_ref(), &[ticket.kind as u8]],
+        bump,
+    )]
+    pub exotic_market: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::LpPosition;
+use crate::errors::GameError;
+
+/// Closes an LP position and returns its rent to the owner, once its shares
+/// have been redeemed.
+pub fn close_lp_position(ctx: Context<CloseLpPosition>) -> Result<()> {
+    let lp_position = &ctx.accounts.lp_position;
+
+    require!(lp_position.shares == 0, GameError::LpSharesOutstanding);
+
+    msg!(
+        "{} closed LP position in battle {}",
+        ctx.accounts.owner.key(),
+        lp_position.battle_id
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseLpPosition<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"lp_position",
+            lp_position.arena.as_ref(),
+            lp_position.battle_id.to_le_bytes().as_ref(),
+            owner.key().as_ref()
+        ],
+        bump = lp_position.bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::state::{BattleState, GlobalState, MarketState, Role};
+use crate::errors::GameError;
+
+/// Closes a settled market once its claim window has passed and every LP,
+/// the house vault included, has withdrawn. Unclaimed winnings and refunds
+/// have expired by then, so everything left (including the rent) is swept to
+/// the treasury.
+pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let now = Clock::get()?.unix_timestamp;
+    let market = &ctx.accounts.market_state;
+
+    require!(battle.is_battle_over, GameError::BattleNotOver);
+    require!(
+        now > battle.claim_deadline(market.config.claim_window_secs, &ctx.accounts.global_state, now),
+        GameError::GracePeriodNotOver
+    );
+
+    // LP capital doesn't expire, so it has to be pulled out first.
+    require!(market.total_lp_shares == 0, GameError::LpSharesOutstanding);
+
+    if market.token_mint != Pubkey::default() {
+        let (Some(mint), Some(vault), Some(treasury_token_account), Some(token_program)) = (
+            &ctx.accounts.token_mint,
+            &ctx.accounts.market_vault,
+            &ctx.accounts.treasury_token_account,
+            &ctx.accounts.token_program,
+        ) else {
+            return Err(GameError::MissingTokenAccounts.into());
+        };
+        require_keys_eq!(mint.key(), market.token_mint, GameError::WrongMint);
+
+        let battle_id_bytes = market.battle_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"market", market.arena.as_ref(), battle_id_bytes.as_ref(), &[market.bump]]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: vault.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: treasury_token_account.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            vault.amount,
+            mint.decimals,
+        )?;
+        close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: vault.to_account_info(),
+                destination: ctx.accounts.treasury.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    msg!(
+        "Market {} closed, {} lamports swept to the treasury",
+        market.battle_id,
+        market.to_account_info().lamports()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        has_one = treasury,
+        constraint = global_state.has_role(&authority.key(), Role::TreasuryManager) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+        close = treasury,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::authority = treasury)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// CHECK: Must match `global_state.treasury`; only receives lamports.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
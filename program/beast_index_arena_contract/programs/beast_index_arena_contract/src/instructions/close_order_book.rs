@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, OrderBook, Role};
+use crate::errors::GameError;
+
+/// Closes a finished battle's order book once every order in it has been settled
+/// or cancelled, handing the rent back to whoever paid it.
+pub fn close_order_book(ctx: Context<CloseOrderBook>) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let book = &ctx.accounts.order_book;
+
+    require!(battle.is_battle_over, GameError::BattleNotOver);
+    require!(book.orders.is_empty(), GameError::OrderStillOpen);
+
+    msg!(
+        "Order book for battle {} closed, {} lamports of rent returned to {}",
+        battle.battle_id,
+        book.to_account_info().lamports(),
+        book.payer
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseOrderBook<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::TreasuryManager) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+        has_one = payer,
+        close = payer,
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    /// CHECK: Must match `order_book.payer`; only receives lamports.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, OrderQueue, Role};
+use crate::errors::GameError;
+
+/// Closes a finished battle's batch queue once every order in it has been settled
+/// or cancelled, handing the rent back to whoever paid it.
+pub fn close_order_queue(ctx: Context<CloseOrderQueue>) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let queue = &ctx.accounts.order_queue;
+
+    require!(battle.is_battle_over, GameError::BattleNotOver);
+    require!(queue.orders.is_empty(), GameError::OrderStillOpen);
+
+    msg!(
+        "Order queue for battle {} closed, {} lamports of rent returned to {}",
+        battle.battle_id,
+        queue.to_account_info().lamports(),
+        queue.payer
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseOrderQueue<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::TreasuryManager) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        mut,
+        seeds = [b"order_queue", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_queue.bump,
+        has_one = payer,
+        close = payer,
+    )]
+    pub order_queue: Account<'info, OrderQueue>,
+
+    /// CHECK: Must match `order_queue.payer`; only receives lamports.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
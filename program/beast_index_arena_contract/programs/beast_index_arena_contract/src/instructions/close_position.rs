@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, OrderBook, OrderQueue, UserPosition};
+use crate::errors::GameError;
+
+/// Closes a position with nothing left to claim and returns its rent to the
+/// owner: claimed or empty, on a creature that lost, or in a market that has
+/// been closed. Settle any queued or resting orders on the creature first,
+/// since they deliver into this position; the close fails while any are left.
+pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+    let position = &ctx.accounts.user_position;
+
+    // Markets only close once the claim window has passed.
+    let mut closable = position.claimed
+        || position.amount == 0
+        || ctx.accounts.market_state.data_is_empty();
+    let battle_info = &ctx.accounts.battle_state;
+    if !closable && !battle_info.data_is_empty() {
+        let battle = BattleState::try_deserialize(&mut &battle_info.try_borrow_data()?[..])?;
+        closable = battle.is_battle_over
+            && battle.winner.is_some_and(|winner| winner != position.creature_index);
+    }
+    require!(closable, GameError::PositionStillOpen);
+
+    let book_info = &ctx.accounts.order_book;
+    if !book_info.data_is_empty() {
+        let book = OrderBook::try_deserialize(&mut &book_info.try_borrow_data()?[..])?;
+        require!(
+            !book.orders.iter().any(|order| {
+                order.owner == position.user && order.creature_index == position.creature_index
+            }),
+            GameError::OrderStillOpen
+        );
+    }
+
+    let queue_info = &ctx.accounts.order_queue;
+    if !queue_info.data_is_empty() {
+        let queue = OrderQueue::try_deserialize(&mut &queue_info.try_borrow_data()?[..])?;
+        require!(
+            !queue.orders.iter().any(|order| {
+                order.user == position.user && order.creature_index == position.creature_index
+            }),
+            GameError::OrderStillOpen
+        );
+    }
+
+    msg!(
+        "{} closed position on Creature {} in battle {}",
+        ctx.accounts.user.key(),
+        position.creature_index,
+        position.battle_id
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            user_position.arena.as_ref(),
+            user_position.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[user_position.creature_index]
+        ],
+        bump = user_position.bump,
+        has_one = user,
+        close = user,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// CHECK: Only read if it exists, to see whether the position lost.
+    #[account(
+        seeds = [b"battle", user_position.arena.as_ref(), user_position.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub battle_state: UncheckedAccount<'info>,
+
+    /// CHECK: Only checked to be empty, i.e. already closed.
+    #[account(
+        seeds = [b"market", user_position.arena.as_ref(), user_position.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_state: UncheckedAccount<'info>,
+
+    /// CHECK: Only read if it exists, to find orders that still deliver into
+    /// the position.
+    #[account(
+        seeds = [b"order_book", user_position.arena.as_ref(), user_position.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub order_book: UncheckedAccount<'info>,
+
+    /// CHECK: Only read if it exists, like the book.
+    #[account(
+        seeds = [b"order_queue", user_position.arena.as_ref(), user_position.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub order_queue: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
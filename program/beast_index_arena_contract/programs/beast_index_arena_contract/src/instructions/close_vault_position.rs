@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::state::VaultPosition;
+use crate::errors::GameError;
+
+/// Closes an emptied house vault position and returns its rent to the owner.
+pub fn close_vault_position(ctx: Context<CloseVaultPosition>) -> Result<()> {
+    require!(
+        ctx.accounts.vault_position.shares == 0,
+        GameError::LpSharesOutstanding
+    );
+
+    msg!("{} closed vault position", ctx.accounts.owner.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseVaultPosition<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault_position", owner.key().as_ref()],
+        bump = vault_position.bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub vault_position: Account<'info, VaultPosition>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::state::{Arena, BattleTemplate, GlobalState, ProtocolConfig, Role};
+use crate::errors::GameError;
+
+/// Opens a new arena whose rounds are built from `template_id`. Without a fee
+/// it takes the protocol default.
+pub fn create_arena(
+    ctx: Context<CreateArena>,
+    name: String,
+    template_id: u64,
+    trade_fee_bps: Option<u16>,
+) -> Result<()> {
+    require!(
+        !name.is_empty() && name.len() <= Arena::MAX_NAME_LEN,
+        GameError::InvalidArenaName
+    );
+
+    let trade_fee_bps = trade_fee_bps.unwrap_or(ctx.accounts.protocol_config.params.trade_fee_bps);
+    Arena::validate_trade_fee(trade_fee_bps)?;
+
+    let arena = &mut ctx.accounts.arena;
+    arena.name = name;
+    arena.current_battle_id = 0;
+    arena.next_battle_id = 1;
+    arena.template_id = template_id;
+    arena.trade_fee_bps = trade_fee_bps;
+    arena.bump = ctx.bumps.arena;
+
+    msg!("Arena {} created with template {}", arena.name, template_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, template_id: u64)]
+pub struct CreateArena<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::Admin) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Only checked to exist.
+    #[account(
+        seeds = [b"template", template_id.to_le_bytes().as_ref()],
+        bump = template.bump,
+    )]
+    pub template: Account<'info, BattleTemplate>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Arena::LEN,
+        seeds = [b"arena", name.as_bytes()],
+        bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{
+    Arena, BattleState, BattleTemplate, GlobalState, LpPosition, MarketState, ProtocolConfig, Role,
+};
+use crate::errors::GameError;
+use super::initialize_battle::init_battle_state;
+use super::initialize_market::init_market_state;
+
+/// Creates the arena's next battle and its market in one transaction from the
+/// arena's template, so a battle can never go live without a market to bet on.
+pub fn create_round(ctx: Context<CreateRound>, initial_liquidity: u64) -> Result<()> {
+    let arena = &ctx.accounts.arena;
+    let battle_id = arena.next_battle_id;
+    let template = &ctx.accounts.template;
+
+    init_battle_state(
+        &mut ctx.accounts.battle_state,
+        arena.key(),
+        battle_id,
+        ctx.accounts.authority.key(),
+        template,
+        None,
+        &ctx.accounts.protocol_config.params,
+        &ctx.accounts.global_state,
+        ctx.bumps.battle_state,
+    )?;
+
+    let market = &mut ctx.accounts.market_state;
+    init_market_state(
+        market,
+        &ctx.accounts.battle_state,
+        initial_liquidity,
+        template.params.market_config,
+        arena.trade_fee_bps,
+        &ctx.accounts.protocol_config.params,
+        ctx.bumps.market_state,
+    )?;
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.liquidity_provider.to_account_info(),
+            to: market.to_account_info(),
+        },
+    );
+    transfer(cpi_context, initial_liquidity)?;
+
+    let lp_position = &mut ctx.accounts.lp_position;
+    lp_position.owner = ctx.accounts.liquidity_provider.key();
+    lp_position.battle_id = battle_id;
+    lp_position.arena = arena.key();
+    lp_position.shares = initial_liquidity;
+    lp_position.deposited = initial_liquidity;
+    lp_position.bump = ctx.bumps.lp_position;
+
+    ctx.accounts.arena.start_battle(battle_id)?;
+
+    msg!(
+        "Round {} created in arena {} with {} lamports of liquidity",
+        battle_id,
+        ctx.accounts.arena.name,
+        initial_liquidity
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateRound<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::BattleCreator) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.name.as_bytes()],
+        bump = arena.bump,
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        seeds = [b"template", arena.template_id.to_le_bytes().as_ref()],
+        bump = template.bump,
+    )]
+    pub template: Account<'info, BattleTemplate>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = BattleState::LEN,
+        seeds = [b"battle", arena.key().as_ref(), arena.next_battle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = MarketState::LEN,
+        seeds = [b"market", arena.key().as_ref(), arena.next_battle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = LpPosition::LEN,
+        seeds = [
+            b"lp_position",
+            arena.key().as_ref(),
+            arena.next_battle_id.to_le_bytes().as_ref(),
+            liquidity_provider.key().as_ref()
+        ],
+        bump
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub liquidity_provider: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleTemplate, GlobalState, Role, TemplateParams};
+use crate::errors::GameError;
+
+/// Stores a new battle template under the next id from the global counter.
+pub fn create_template(ctx: Context<CreateTemplate>, params: TemplateParams) -> Result<()> {
+    params.validate()?;
+
+    let global_state = &mut ctx.accounts.global_state;
+    let template_id = global_state.next_template_id;
+    global_state.next_template_id = template_id
+        .checked_add(1)
+        .ok_or(GameError::CalculationOverflow)?;
+
+    let template = &mut ctx.accounts.template;
+    template.template_id = template_id;
+    template.version = 1;
+    template.params = params;
+    template.bump = ctx.bumps.template;
+
+    msg!("Battle template {} created", template_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateTemplate<'info> {
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::Admin) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = BattleTemplate::LEN,
+        seeds = [b"template", global_state.next_template_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub template: Account<'info, BattleTemplate>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{GlobalState, HouseVault, VaultPosition};
+use crate::errors::GameError;
+
+/// Buys vault shares at the vault's current value. `min_shares` guards
+/// against the price moving before the deposit lands.
+pub fn deposit_to_vault(ctx: Context<DepositToVault>, amount: u64, min_shares: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.house_vault;
+    let position = &mut ctx.accounts.vault_position;
+
+    require!(amount > 0, GameError::BetTooSmall);
+    // Deployed capital is only known at cost until its markets settle, so
+    // shares can't be priced fairly while any is out.
+    require!(vault.deployed == 0, GameError::VaultCapitalDeployed);
+
+    let shares = if vault.total_shares == 0 || vault.total_assets == 0 {
+        amount
+    } else {
+        (amount as u128)
+            .checked_mul(vault.total_shares as u128)
+            .ok_or(GameError::CalculationOverflow)?
+            .checked_div(vault.total_assets as u128)
+            .ok_or(GameError::DivisionByZero)? as u64
+    };
+    require!(shares > 0, GameError::ZeroShares);
+    require!(shares >= min_shares, GameError::SlippageExceeded);
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: vault.to_account_info(),
+        },
+    );
+    transfer(cpi_context, amount)?;
+
+    if position.owner == Pubkey::default() {
+        position.owner = ctx.accounts.owner.key();
+        position.bump = ctx.bumps.vault_position;
+    }
+    position.shares = position
+        .shares
+        .checked_add(shares)
+        .ok_or(GameError::CalculationOverflow)?;
+    vault.total_shares = vault
+        .total_shares
+        .checked_add(shares)
+        .ok_or(GameError::CalculationOverflow)?;
+    vault.total_assets = vault
+        .total_assets
+        .checked_add(amount)
+        .ok_or(GameError::CalculationOverflow)?;
+
+    msg!(
+        "{} deposited {} lamports into the house vault for {} shares",
+        ctx.accounts.owner.key(),
+        amount,
+        shares
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositToVault<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"house_vault"],
+        bump = house_vault.bump,
+    )]
+    pub house_vault: Account<'info, HouseVault>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = VaultPosition::LEN,
+        seeds = [b"vault_position", owner.key().as_ref()],
+        bump
+    )]
+    pub vault_position: Account<'info, VaultPosition>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
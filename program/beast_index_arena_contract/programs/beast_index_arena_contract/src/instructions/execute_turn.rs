@@ -1,13 +1,26 @@
 use anchor_lang::prelude::*;
-use crate::state::BattleState;
+use crate::state::{BatchOrder, BattleState, GlobalState, MarketState, OrderBook, OrderQueue, Role};
+use crate::funds::crank_order_book;
 use crate::errors::GameError;
-use crate::utils::{get_random_seed, pick_random_target, pick_random_ability, calculate_damage};
+use crate::utils::{
+    get_random_seed, order_by_initiative, pick_random_target, pick_random_ability, calculate_damage,
+    clear_batch,
+};
 
 pub fn execute_turn(ctx: Context<ExecuteTurn>) -> Result<()> {
     let battle = &mut ctx.accounts.battle_state;
+    let global_state = &ctx.accounts.global_state;
     let clock = &ctx.accounts.clock;
 
     require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+
+    // Time spent with turns paused protocol-wide doesn't count against the battle.
+    battle.catch_up_turns_pause(global_state, clock.unix_timestamp);
+    require!(
+        clock.unix_timestamp >= battle.start_time,
+        GameError::BattleNotStarted
+    );
 
     let time_since_last_turn = clock.unix_timestamp - battle.last_turn_time;
     require!(
@@ -15,23 +28,196 @@ pub fn execute_turn(ctx: Context<ExecuteTurn>) -> Result<()> {
         GameError::TurnIntervalNotMet
     );
 
+    // Orders queued since the last turn clear before this turn's attacks land.
+    if battle.has_order_queue {
+        let (Some(market), Some(queue)) = (
+            ctx.accounts.market_state.as_mut(),
+            ctx.accounts.order_queue.as_mut(),
+        ) else {
+            return Err(GameError::MissingOrderQueue.into());
+        };
+        clear_order_queue(market, queue)?;
+    }
+
+    // Limit orders crossed since the last trade fill before the attacks land.
+    if battle.has_order_book {
+        let (Some(market), Some(book)) = (
+            ctx.accounts.market_state.as_mut(),
+            ctx.accounts.order_book.as_mut(),
+        ) else {
+            return Err(GameError::MissingOrderBook.into());
+        };
+        crank_order_book(market, book, battle, clock.unix_timestamp)?;
+    }
+
     let battle_duration = clock.unix_timestamp - battle.start_time;
     if battle_duration > battle.max_duration {
         battle.is_battle_over = true;
-        battle.winner = None;
+        battle.winner = battle.timeout_winner();
+        battle.last_turn_time = clock.unix_timestamp;
+        battle.ended_at = clock.unix_timestamp;
+        battle.claims_pause_at_end = global_state.claims_paused_total(clock.unix_timestamp);
         msg!("Battle timed out after {} seconds!", battle_duration);
+        if let Some(winner) = battle.winner {
+            msg!("Creature {} wins on the tiebreak!", winner);
+        }
         return Ok(());
     }
 
-    let mut creature_order: Vec<(usize, u16)> = Vec::new();
-    for i in 0..4 {
-        if battle.is_alive[i] {
-            creature_order.push((i, battle.creature_spd[i]));
+    resolve_attacks(battle, clock);
+    if battle.is_battle_over {
+        battle.claims_pause_at_end = global_state.claims_paused_total(clock.unix_timestamp);
+    }
+
+    battle.last_turn_time = clock.unix_timestamp;
+
+    battle.current_turn += 1;
+    Ok(())
+}
+
+fn clear_order_queue(
+    market: &mut Account<MarketState>,
+    queue: &mut Account<OrderQueue>,
+) -> Result<()> {
+    let mut lamports_in: u64 = 0;
+
+    for creature_index in 0..4u8 {
+        let mut buy_lamports: u64 = 0;
+        let mut sell_shares: u64 = 0;
+        for order in queue.orders.iter() {
+            if order.creature_index != creature_index || order.is_cleared {
+                continue;
+            }
+            if order.is_buy {
+                buy_lamports = buy_lamports.checked_add(order.amount).ok_or(GameError::CalculationOverflow)?;
+            } else {
+                sell_shares = sell_shares.checked_add(order.amount).ok_or(GameError::CalculationOverflow)?;
+            }
+        }
+        if buy_lamports == 0 && sell_shares == 0 {
+            continue;
         }
+
+        let (current_shares, current_pool) = match creature_index {
+            0 => (market.creature_0_shares, market.creature_0_pool),
+            1 => (market.creature_1_shares, market.creature_1_pool),
+            2 => (market.creature_2_shares, market.creature_2_pool),
+            3 => (market.creature_3_shares, market.creature_3_pool),
+            _ => return Err(GameError::InvalidCreatureIndex.into()),
+        };
+
+        // Buyers pay the trade fee like any other bet; it stays in the market
+        // and only the net clears.
+        let (net_buy_lamports, fee) = market.split_trade_fee(buy_lamports)?;
+        let cleared = clear_batch(current_shares, market.k_constant, net_buy_lamports, sell_shares)
+            .ok()
+            .and_then(|(to_buyers, to_sellers)| {
+                let new_shares = current_shares.checked_sub(to_buyers)?.checked_add(sell_shares)?;
+                let new_pool = current_pool.checked_add(net_buy_lamports)?.checked_sub(to_sellers)?;
+                let new_total = market.total_pool.checked_add(net_buy_lamports)?.checked_sub(to_sellers)?;
+                Some((to_buyers, to_sellers, new_shares, new_pool, new_total))
+            });
+        let Some((to_buyers, to_sellers, new_shares, new_pool, new_total)) = cleared else {
+            msg!("Batch for Creature {} could not clear, orders stay queued", creature_index);
+            continue;
+        };
+
+        // Fills and cost bases are pro rata and round down; the last order on
+        // each side takes what's left, so every cleared share and lamport is
+        // owed to someone.
+        let is_open = |order: &BatchOrder, is_buy: bool| {
+            order.creature_index == creature_index && !order.is_cleared && order.is_buy == is_buy
+        };
+        let last_buy = queue.orders.iter().rposition(|order| is_open(order, true));
+        let last_sell = queue.orders.iter().rposition(|order| is_open(order, false));
+        let mut shares_left = to_buyers;
+        let mut lamports_left = to_sellers;
+        let mut cost_left = net_buy_lamports;
+        let mut sold_cost: u64 = 0;
+        for (i, order) in queue.orders.iter_mut().enumerate() {
+            if order.creature_index != creature_index || order.is_cleared {
+                continue;
+            }
+            if order.is_buy {
+                (order.fill, order.cost_basis) = if Some(i) == last_buy {
+                    (shares_left, cost_left)
+                } else {
+                    (
+                        (order.amount as u128 * to_buyers as u128 / buy_lamports as u128) as u64,
+                        (order.amount as u128 * net_buy_lamports as u128 / buy_lamports as u128) as u64,
+                    )
+                };
+                shares_left -= order.fill;
+                cost_left -= order.cost_basis;
+            } else {
+                order.fill = if Some(i) == last_sell {
+                    lamports_left
+                } else {
+                    (order.amount as u128 * to_sellers as u128 / sell_shares as u128) as u64
+                };
+                lamports_left -= order.fill;
+                sold_cost = sold_cost.saturating_add(order.cost_basis);
+            }
+            order.is_cleared = true;
+        }
+
+        match creature_index {
+            0 => {
+                market.creature_0_shares = new_shares;
+                market.creature_0_pool = new_pool;
+            }
+            1 => {
+                market.creature_1_shares = new_shares;
+                market.creature_1_pool = new_pool;
+            }
+            2 => {
+                market.creature_2_shares = new_shares;
+                market.creature_2_pool = new_pool;
+            }
+            3 => {
+                market.creature_3_shares = new_shares;
+                market.creature_3_pool = new_pool;
+            }
+            _ => return Err(GameError::InvalidCreatureIndex.into()),
+        }
+        market.total_pool = new_total;
+        // Sold shares' basis leaves with them; settling only pays out lamports.
+        market.add_cost_basis(creature_index, net_buy_lamports)?;
+        market.remove_cost_basis(creature_index, sold_cost)?;
+        market.batch_fills_owed = market
+            .batch_fills_owed
+            .checked_add(to_sellers)
+            .ok_or(GameError::CalculationOverflow)?;
+        lamports_in += buy_lamports;
+
+        msg!(
+            "Batch {} cleared Creature {}: {} lamports ({} fee) bought {} shares, {} shares sold for {} lamports",
+            queue.batch_number,
+            creature_index,
+            buy_lamports,
+            fee,
+            to_buyers,
+            sell_shares,
+            to_sellers
+        );
     }
-    creature_order.sort_by(|a, b| b.1.cmp(&a.1));
 
-    for (attacker_idx, _speed) in creature_order {
+    **queue.to_account_info().try_borrow_mut_lamports()? -= lamports_in;
+    **market.to_account_info().try_borrow_mut_lamports()? += lamports_in;
+
+    queue.batch_number += 1;
+    Ok(())
+}
+
+fn resolve_attacks(battle: &mut BattleState, clock: &Clock) {
+    // Each turn owns ten salts: 0-3 pick targets, 4 rolls initiative, 5-8 pick abilities.
+    let initiative_seed = get_random_seed(
+        clock,
+        battle.current_turn.wrapping_mul(10).wrapping_add(4),
+    );
+    let creature_order = order_by_initiative(&battle.creature_spd, &battle.is_alive, initiative_seed);
+
+    for attacker_idx in creature_order {
         if !battle.is_alive[attacker_idx] {
             continue;
         }
@@ -49,14 +235,14 @@ pub fn execute_turn(ctx: Context<ExecuteTurn>) -> Result<()> {
 
         let ability_seed = get_random_seed(
             clock,
-            battle.current_turn.wrapping_mul(100).wrapping_add(attacker_idx as u64),
+            battle.current_turn.wrapping_mul(10).wrapping_add(5 + attacker_idx as u64),
         );
         let ability = pick_random_ability(ability_seed);
 
         let damage = calculate_damage(
             battle.creature_atk[attacker_idx],
             battle.creature_def[target_idx],
-            ability,
+            battle.ability_multiplier_bps(ability),
         );
 
         battle.creature_hp[target_idx] = battle.creature_hp[target_idx].saturating_sub(damage);
@@ -72,6 +258,8 @@ pub fn execute_turn(ctx: Context<ExecuteTurn>) -> Result<()> {
 
         if battle.creature_hp[target_idx] == 0 {
             battle.is_alive[target_idx] = false;
+            battle.death_order[battle.death_count as usize] = target_idx as u8;
+            battle.death_count += 1;
             msg!(" Creature {} died!", target_idx);
         }
     }
@@ -85,28 +273,155 @@ pub fn execute_turn(ctx: Context<ExecuteTurn>) -> Result<()> {
 
     if alive_creatures.len() == 1 {
         battle.is_battle_over = true;
+        battle.ended_at = clock.unix_timestamp;
         battle.winner = Some(alive_creatures[0] as u8);
         msg!("Creature {} WINS!", alive_creatures[0]);
-    } else if alive_creatures.len() == 0 {
+    } else if alive_creatures.is_empty() {
         battle.is_battle_over = true;
+        battle.ended_at = clock.unix_timestamp;
         battle.winner = None;
         msg!("All creatures died! It's a draw!");
     }
-
-    battle.last_turn_time = clock.unix_timestamp;
-
-    battle.current_turn += 1;
-    Ok(())
 }
 
 #[derive(Accounts)]
 pub struct ExecuteTurn<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&executer.key(), Role::CrankOperator) @ GameError::MissingRole,
+        constraint = !global_state.turns_paused @ GameError::TurnsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
     #[account(
         mut,
-        seeds = [b"battle", battle_state.battle_id.to_le_bytes().as_ref()],
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
         bump = battle_state.bump,
     )]
     pub battle_state: Account<'info, BattleState>,
+
+    /// Only needed for batch-mode markets or markets with an order book, which
+    /// settle at the start of the turn. Required once the queue or book exists.
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Option<Account<'info, MarketState>>,
+
+    #[account(
+        mut,
+        seeds = [b"order_queue", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_queue.bump,
+    )]
+    pub order_queue: Option<Account<'info, OrderQueue>>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Option<Account<'info, OrderBook>>,
+
     pub executer: Signer<'info>,
     pub clock: Sysvar<'info, Clock>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{GlobalState, Tiebreak};
+
+    fn simulate_battle(battle_no: u64) -> Option<u8> {
+        let mut battle = BattleState {
+            creature_hp: [100; 4],
+            creature_max_hp: [100; 4],
+            creature_atk: [50; 4],
+            creature_def: [20; 4],
+            creature_spd: [30; 4],
+            is_alive: [true; 4],
+            ability_multipliers_bps: [10_000, 15_000, 7_500],
+            ..Default::default()
+        };
+        while !battle.is_battle_over {
+            let clock = Clock {
+                slot: battle_no.wrapping_mul(7_919).wrapping_add(battle.current_turn * 3),
+                unix_timestamp: 1_700_000_000 + (battle_no * 1_000 + battle.current_turn * 5) as i64,
+                ..Default::default()
+            };
+            resolve_attacks(&mut battle, &clock);
+            battle.current_turn += 1;
+        }
+        battle.winner
+    }
+
+    #[test]
+    fn equal_stats_give_even_win_rates() {
+        const BATTLES: u64 = 4_000;
+        let mut wins = [0u64; 4];
+        for battle_no in 0..BATTLES {
+            if let Some(winner) = simulate_battle(battle_no) {
+                wins[winner as usize] += 1;
+            }
+        }
+
+        let decided: u64 = wins.iter().sum();
+        for (creature, &count) in wins.iter().enumerate() {
+            let rate = count as f64 / decided as f64;
+            assert!(
+                (0.22..=0.28).contains(&rate),
+                "creature {} won {:.3} of battles ({:?})",
+                creature,
+                rate,
+                wins
+            );
+        }
+    }
+
+    #[test]
+    fn highest_hp_tiebreak_picks_the_healthiest_survivor() {
+        let mut battle = BattleState {
+            creature_hp: [40, 0, 75, 60],
+            is_alive: [true, false, true, true],
+            ..Default::default()
+        };
+        assert_eq!(battle.timeout_winner(), None);
+
+        battle.tiebreak = Tiebreak::HighestHp;
+        assert_eq!(battle.timeout_winner(), Some(2));
+
+        battle.creature_hp[3] = 75;
+        assert_eq!(battle.timeout_winner(), None);
+    }
+
+    #[test]
+    fn global_pauses_shift_turn_clock_and_claim_window() {
+        let mut global = GlobalState::default();
+        let mut battle = BattleState {
+            start_time: 1_000,
+            last_turn_time: 1_000,
+            ..Default::default()
+        };
+
+        // A turns pause that started before the battle only counts from its creation.
+        global.set_pause(false, true, false, 900);
+        battle.turns_pause_applied = global.turns_paused_total(1_000);
+        global.set_pause(false, false, false, 1_300);
+        battle.catch_up_turns_pause(&global, 1_400);
+        assert_eq!((battle.start_time, battle.last_turn_time), (1_300, 1_300));
+
+        // Already applied pauses don't shift it again.
+        battle.catch_up_turns_pause(&global, 1_500);
+        assert_eq!(battle.start_time, 1_300);
+
+        battle.ended_at = 2_000;
+        battle.claims_pause_at_end = global.claims_paused_total(2_000);
+        assert_eq!(battle.claim_deadline(500, &global, 2_100), 2_500);
+
+        global.set_pause(false, false, true, 2_400);
+        assert_eq!(battle.claim_deadline(500, &global, 2_600), 2_700);
+        global.set_pause(false, false, false, 3_000);
+        assert_eq!(battle.claim_deadline(500, &global, 3_000), 3_100);
+    }
+}
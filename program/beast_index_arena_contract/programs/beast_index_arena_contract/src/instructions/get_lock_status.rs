@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, MarketLockStatus, MarketState};
+use crate::utils;
+
+/// Read-only: returns whether the market currently accepts bets and sells.
+pub fn get_lock_status(ctx: Context<GetLockStatus>) -> Result<MarketLockStatus> {
+    let clock = Clock::get()?;
+    Ok(utils::get_lock_status(
+        &ctx.accounts.market_state.config,
+        &ctx.accounts.battle_state,
+        clock.unix_timestamp,
+    ))
+}
+
+#[derive(Accounts)]
+pub struct GetLockStatus<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalState, Role};
+use crate::errors::GameError;
+
+pub fn grant_role(ctx: Context<GrantRole>, member: Pubkey, role: Role) -> Result<()> {
+    ctx.accounts.global_state.grant_role(member, role)?;
+    msg!("Granted {:?} to {}", role, member);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GrantRole<'info> {
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::Admin) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub authority: Signer<'info>,
+}
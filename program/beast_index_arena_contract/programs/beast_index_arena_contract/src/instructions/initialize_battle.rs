@@ -1,50 +1,156 @@
 use anchor_lang::prelude::*;
-use crate::state::BattleState;
+use crate::state::{Arena, BattleState, BattleTemplate, GlobalState, ProtocolConfig, ProtocolParams, Role};
+use crate::errors::GameError;
 
+/// Creates the arena's next battle from a template. Without a `start_time` the
+/// template's betting window decides when the first turn can run.
 pub fn initialize_battle(
     ctx: Context<InitializeBattle>,
+    template_id: u64,
+    start_time: Option<i64>,
+) -> Result<()> {
+    let battle_id = ctx.accounts.arena.next_battle_id;
+    init_battle_state(
+        &mut ctx.accounts.battle_state,
+        ctx.accounts.arena.key(),
+        battle_id,
+        ctx.accounts.authority.key(),
+        &ctx.accounts.template,
+        start_time,
+        &ctx.accounts.protocol_config.params,
+        &ctx.accounts.global_state,
+        ctx.bumps.battle_state,
+    )?;
+    msg!(
+        "Battle {} created from template {} version {}",
+        battle_id,
+        template_id,
+        ctx.accounts.template.version
+    );
+    ctx.accounts.arena.start_battle(battle_id)
+}
+
+/// Fills in a fresh battle. Its id always comes from its arena's `next_battle_id`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn init_battle_state(
+    battle: &mut BattleState,
+    arena: Pubkey,
     battle_id: u64,
-    hp: u16,
-    atk: u16,
-    def: u16,
-    spd: u16,
-    turn_interval: i64,
-    max_duration: i64,
+    authority: Pubkey,
+    template: &BattleTemplate,
+    start_time: Option<i64>,
+    params: &ProtocolParams,
+    global: &GlobalState,
+    bump: u8,
 ) -> Result<()> {
-    let battle = &mut ctx.accounts.battle_state;
     let clock = Clock::get()?;
+
+    let rules = &template.params;
+
+    // A future start time opens a betting window before the first turn.
+    let start_time = match start_time {
+        Some(start_time) => start_time,
+        None => clock.unix_timestamp.saturating_add(rules.betting_window_secs),
+    };
+    require!(start_time >= clock.unix_timestamp, GameError::InvalidStartTime);
+
     battle.battle_id = battle_id;
-    battle.authority = ctx.accounts.authority.key();
+    battle.arena = arena;
+    battle.authority = authority;
+    battle.template_id = template.template_id;
+    battle.template_version = template.version;
 
-    battle.creature_hp = [hp, hp, hp, hp];
-    battle.creature_atk = [atk, atk, atk, atk];
-    battle.creature_def = [def, def, def, def];
-    battle.creature_max_hp = [hp, hp, hp, hp];
-    battle.creature_spd = [spd, spd, spd, spd];
+    battle.creature_hp = rules.creature_hp;
+    battle.creature_atk = rules.creature_atk;
+    battle.creature_def = rules.creature_def;
+    battle.creature_max_hp = rules.creature_hp;
+    battle.creature_spd = rules.creature_spd;
     battle.is_alive = [true, true, true, true];
 
     battle.is_battle_over = false;
     battle.winner = None;
     battle.current_turn = 0;
 
-    battle.start_time = clock.unix_timestamp;
-    battle.last_turn_time = clock.unix_timestamp;
-    battle.turn_interval = turn_interval;
-    battle.max_duration = max_duration;
+    battle.start_time = start_time;
+    battle.last_turn_time = start_time;
+    battle.turn_interval = match rules.turn_interval {
+        0 => params.default_turn_interval,
+        secs => secs,
+    };
+    battle.max_duration = match rules.max_duration {
+        0 => params.default_max_duration,
+        secs => secs,
+    };
+    for (i, multiplier) in battle.ability_multipliers_bps.iter_mut().enumerate() {
+        *multiplier = match rules.ability_multipliers_bps[i] {
+            0 => params.ability_multipliers_bps[i],
+            bps => bps,
+        };
+    }
+    battle.tiebreak = rules.tiebreak;
 
-    battle.bump = ctx.bumps.battle_state;
+    battle.is_paused = false;
+    battle.paused_at = 0;
+    battle.is_cancelled = false;
+
+    battle.death_order = [0; 4];
+    battle.death_count = 0;
+    battle.ended_at = 0;
+    battle.pending_authority = Pubkey::default();
+    battle.has_order_queue = false;
+    battle.has_order_book = false;
+    battle.open_exotic_markets = 0;
+    // Only pauses from here on shift this battle's clock.
+    battle.turns_pause_applied = global.turns_paused_total(clock.unix_timestamp);
+    battle.claims_pause_at_end = 0;
+
+    battle.bump = bump;
+
+    if start_time > clock.unix_timestamp {
+        msg!("Battle {} scheduled to start at {}", battle_id, start_time);
+    }
 
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(battle_id: u64)]
+#[instruction(template_id: u64)]
 pub struct InitializeBattle<'info> {
+    /// Only battle creators can create battles.
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::BattleCreator) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Battle ids come from the arena's counter.
+    #[account(
+        mut,
+        seeds = [b"arena", arena.name.as_bytes()],
+        bump = arena.bump,
+    )]
+    pub arena: Account<'info, Arena>,
+
+    /// Has to be the template the arena runs its battles from.
+    #[account(
+        seeds = [b"template", template_id.to_le_bytes().as_ref()],
+        bump = template.bump,
+        constraint = template.template_id == arena.template_id @ GameError::TemplateMismatch,
+    )]
+    pub template: Account<'info, BattleTemplate>,
+
     #[account(
         init,
         payer = authority,
         space = BattleState::LEN,
-        seeds = [b"battle", battle_id.to_le_bytes().as_ref()],
+        seeds = [b"battle", arena.key().as_ref(), arena.next_battle_id.to_le_bytes().as_ref()],
         bump
     )]
     pub battle_state: Account<'info, BattleState>,
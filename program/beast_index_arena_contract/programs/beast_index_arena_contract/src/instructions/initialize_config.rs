@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalState, ProtocolConfig, ProtocolParams};
+use crate::errors::GameError;
+
+pub fn initialize_config(ctx: Context<InitializeConfig>, params: ProtocolParams) -> Result<()> {
+    params.validate()?;
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.version = 1;
+    config.params = params;
+    config.bump = ctx.bumps.protocol_config;
+    msg!("Protocol config initialized");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ProtocolConfig::LEN,
+        seeds = [b"protocol_config"],
+        bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, ExoticKind, ExoticMarket, GlobalState, OverUnderLine, ProtocolConfig, Role};
+use crate::errors::GameError;
+
+pub fn initialize_exotic_market(ctx: Context<InitializeExoticMarket>, kind: ExoticKind) -> Result<()> {
+    require!(kind != ExoticKind::OverUnder, GameError::InvalidSelection);
+    open_exotic_market(
+        &mut ctx.accounts.battle_state,
+        &mut ctx.accounts.exotic_market,
+        kind,
+        OverUnderLine::default(),
+        ctx.accounts.protocol_config.params.default_claim_window_secs,
+        ctx.bumps.exotic_market,
+    )
+}
+
+pub(crate) fn open_exotic_market(
+    battle: &mut BattleState,
+    exotic: &mut ExoticMarket,
+    kind: ExoticKind,
+    line: OverUnderLine,
+    claim_window_secs: i64,
+    bump: u8,
+) -> Result<()> {
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(battle.death_count == 0, GameError::ExoticBettingClosed);
+
+    exotic.battle_id = battle.battle_id;
+    exotic.kind = kind;
+    exotic.pools = [0; ExoticKind::MAX_POOLS];
+    exotic.round_totals = [0; 3];
+    exotic.settled_rounds = 0;
+    exotic.winning_outcomes = [None; 3];
+    exotic.line = line;
+    exotic.claim_window_secs = claim_window_secs;
+    exotic.bump = bump;
+    battle.open_exotic_markets += 1;
+
+    msg!("{:?} market opened for battle {}", kind, battle.battle_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(kind: ExoticKind)]
+pub struct InitializeExoticMarket<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::BattleCreator) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// Counts its exotic markets, so it can't be closed before them.
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ExoticMarket::LEN,
+        seeds = [b"exotic", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[kind as u8]],
+        bump
+    )]
+    pub exotic_market: Account<'info, ExoticMarket>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
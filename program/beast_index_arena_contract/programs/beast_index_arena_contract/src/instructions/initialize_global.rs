@@ -1,10 +1,20 @@
 use anchor_lang::prelude::*;
-use crate::state::GlobalState;
+use crate::state::{GlobalState, RoleMember};
 
 pub fn initialize_global(ctx: Context<InitializeGlobal>) -> Result<()> {
     let global_state = &mut ctx.accounts.global_state;
-    global_state.current_battle_id = 0;
     global_state.authority = ctx.accounts.authority.key();
+    global_state.treasury = ctx.accounts.authority.key();
+    global_state.role_members = [RoleMember::default(); GlobalState::MAX_ROLE_MEMBERS];
+    global_state.pending_authority = Pubkey::default();
+    global_state.trading_paused = false;
+    global_state.turns_paused = false;
+    global_state.claims_paused = false;
+    global_state.next_template_id = 1;
+    global_state.turns_paused_at = 0;
+    global_state.claims_paused_at = 0;
+    global_state.turns_paused_secs = 0;
+    global_state.claims_paused_secs = 0;
     global_state.bump = ctx.bumps.global_state;
     msg!("Global state initialized");
     Ok(())
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalState, HouseVault, Role};
+use crate::errors::GameError;
+
+pub fn initialize_house_vault(ctx: Context<InitializeHouseVault>) -> Result<()> {
+    let vault = &mut ctx.accounts.house_vault;
+    vault.total_shares = 0;
+    vault.total_assets = 0;
+    vault.deployed = 0;
+    vault.bump = ctx.bumps.house_vault;
+    msg!("House vault initialized");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeHouseVault<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = HouseVault::LEN,
+        seeds = [b"house_vault"],
+        bump
+    )]
+    pub house_vault: Account<'info, HouseVault>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::TreasuryManager) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
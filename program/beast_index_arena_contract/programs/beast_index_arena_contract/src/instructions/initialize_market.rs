@@ -1,14 +1,77 @@
 use anchor_lang::prelude::*;
-use crate::state::MarketState;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{
+    Arena, BattleState, BattleTemplate, GlobalState, LpPosition, MarketConfig, MarketState, ProtocolConfig,
+    ProtocolParams, Role,
+};
+use crate::errors::GameError;
 
+/// Opens the AMM for a battle. Without a `config` the market uses the one
+/// from the battle's template.
 pub fn initialize_market(
     ctx: Context<InitializeMarket>,
     battle_id: u64,
     initial_liquidity: u64,
+    config: Option<MarketConfig>,
 ) -> Result<()> {
     let market = &mut ctx.accounts.market_state;
+    init_market_state(
+        market,
+        &ctx.accounts.battle_state,
+        initial_liquidity,
+        config.unwrap_or(ctx.accounts.template.params.market_config),
+        ctx.accounts.arena.trade_fee_bps,
+        &ctx.accounts.protocol_config.params,
+        ctx.bumps.market_state,
+    )?;
 
-    market.battle_id = battle_id;
+    // The curve's liquidity is backed by real lamports from the liquidity provider.
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.liquidity_provider.to_account_info(),
+            to: market.to_account_info(),
+        },
+    );
+    transfer(cpi_context, initial_liquidity)?;
+
+    let lp_position = &mut ctx.accounts.lp_position;
+    lp_position.owner = ctx.accounts.liquidity_provider.key();
+    lp_position.battle_id = battle_id;
+    lp_position.arena = ctx.accounts.arena.key();
+    lp_position.shares = initial_liquidity;
+    lp_position.deposited = initial_liquidity;
+    lp_position.bump = ctx.bumps.lp_position;
+
+    msg!(
+        "Market initialized for battle {} with {} lamports of liquidity",
+        battle_id,
+        initial_liquidity
+    );
+
+    Ok(())
+}
+
+/// Sets up a fresh market for `battle` whose curve and LP shares both start
+/// at `initial_liquidity`, taking its price scale and any claim window
+/// `config` leaves open from the protocol config.
+pub(crate) fn init_market_state(
+    market: &mut MarketState,
+    battle: &BattleState,
+    initial_liquidity: u64,
+    mut config: MarketConfig,
+    trade_fee_bps: u16,
+    params: &ProtocolParams,
+    bump: u8,
+) -> Result<()> {
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(
+        config.lock_before_end_secs >= 0 && config.lock_before_end_secs < battle.max_duration,
+        GameError::InvalidMarketConfig
+    );
+
+    market.battle_id = battle.battle_id;
+    market.arena = battle.arena;
     market.creature_0_pool = 0;
     market.creature_1_pool = 0;
     market.creature_2_pool = 0;
@@ -21,27 +84,89 @@ pub fn initialize_market(
     market.creature_2_shares = initial_liquidity;
     market.creature_3_shares = initial_liquidity;
     market.k_constant = (initial_liquidity as u128).pow(2);
-    market.bump = ctx.bumps.market_state;
-
-    msg!("Market initialized for battle {}", battle_id);
+    market.initial_liquidity = initial_liquidity;
+    market.refund_mode = false;
+    if config.claim_window_secs == 0 {
+        config.claim_window_secs = params.default_claim_window_secs;
+    }
+    market.config = config;
 
+    market.lp_deposit = initial_liquidity;
+    market.total_lp_shares = initial_liquidity;
+    market.winning_shares_claimed = 0;
+    market.batch_fills_owed = 0;
+    market.token_mint = Pubkey::default();
+    market.tokenized_shares = false;
+    market.trade_fee_bps = trade_fee_bps;
+    market.cost_basis_outstanding = [0; 4];
+    market.price_scale = params.price_scale;
+    market.bump = bump;
     Ok(())
 }
 
 #[derive(Accounts)]
 #[instruction(battle_id: u64)]
 pub struct InitializeMarket<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::BattleCreator) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"arena", arena.name.as_bytes()],
+        bump = arena.bump,
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"battle", arena.key().as_ref(), battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"template", battle_state.template_id.to_le_bytes().as_ref()],
+        bump = template.bump,
+    )]
+    pub template: Account<'info, BattleTemplate>,
+
     #[account(
         init,
         payer = authority,
         space = MarketState::LEN,
-        seeds = [b"market", battle_id.to_le_bytes().as_ref()],
+        seeds = [b"market", arena.key().as_ref(), battle_id.to_le_bytes().as_ref()],
         bump
     )]
     pub market_state: Account<'info, MarketState>,
 
+    #[account(
+        init,
+        payer = authority,
+        space = LpPosition::LEN,
+        seeds = [
+            b"lp_position",
+            arena.key().as_ref(),
+            battle_id.to_le_bytes().as_ref(),
+            liquidity_provider.key().as_ref()
+        ],
+        bump
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    #[account(mut)]
+    pub liquidity_provider: Signer<'info>,
+
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::state::{BattleState, GlobalState, MarketState, Role};
+use crate::errors::GameError;
+
+/// Switches a fresh market to outcome tokens: one mint per creature, with the
+/// market PDA as mint authority.
+pub fn initialize_outcome_mints(ctx: Context<InitializeOutcomeMints>, _decimals: u8) -> Result<()> {
+    let market = &mut ctx.accounts.market_state;
+
+    require!(!market.config.batch_mode, GameError::BatchModeActive);
+    require!(market.total_pool == 0, GameError::MarketAlreadyTrading);
+
+    market.tokenized_shares = true;
+
+    msg!("Outcome mints created for battle {}", market.battle_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(decimals: u8)]
+pub struct InitializeOutcomeMints<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::BattleCreator) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"outcome_mint", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[0]],
+        bump,
+        mint::decimals = decimals,
+        mint::authority = market_state,
+        mint::token_program = token_program,
+    )]
+    pub outcome_mint_0: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"outcome_mint", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[1]],
+        bump,
+        mint::decimals = decimals,
+        mint::authority = market_state,
+        mint::token_program = token_program,
+    )]
+    pub outcome_mint_1: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"outcome_mint", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[2]],
+        bump,
+        mint::decimals = decimals,
+        mint::authority = market_state,
+        mint::token_program = token_program,
+    )]
+    pub outcome_mint_2: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"outcome_mint", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[3]],
+        bump,
+        mint::decimals = decimals,
+        mint::authority = market_state,
+        mint::token_program = token_program,
+    )]
+    pub outcome_mint_3: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, ExoticKind, ExoticMarket, GlobalState, OverUnderLine, ProtocolConfig, Role};
+use crate::errors::GameError;
+use super::initialize_exotic_market::open_exotic_market;
+
+/// Opens an over/under market on battle length, measured in turns or seconds.
+/// Betting runs until the first turn.
+pub fn initialize_over_under_market(ctx: Context<InitializeOverUnderMarket>, line: OverUnderLine) -> Result<()> {
+    require!(line.value > 0, GameError::ZeroAmount);
+    require!(ctx.accounts.battle_state.current_turn == 0, GameError::ExoticBettingClosed);
+
+    open_exotic_market(
+        &mut ctx.accounts.battle_state,
+        &mut ctx.accounts.exotic_market,
+        ExoticKind::OverUnder,
+        line,
+        ctx.accounts.protocol_config.params.default_claim_window_secs,
+        ctx.bumps.exotic_market,
+    )
+}
+
+#[derive(Accounts)]
+pub struct InitializeOverUnderMarket<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::BattleCreator) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// Counts its exotic markets, like `initialize_exotic_market`.
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ExoticMarket::LEN,
+        seeds = [b"exotic", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[ExoticKind::OverUnder as u8]],
+        bump
+    )]
+    pub exotic_market: Account<'info, ExoticMarket>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
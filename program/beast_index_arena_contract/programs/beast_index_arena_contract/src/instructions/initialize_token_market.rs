@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::state::{
+    Arena, BattleState, BattleTemplate, GlobalState, LpPosition, MarketConfig, MarketState, ProtocolConfig,
+    Role,
+};
+use crate::errors::GameError;
+use super::initialize_market::init_market_state;
+
+/// Same as `initialize_market`, but the market is priced in `token_mint` and
+/// holds its funds in a token vault owned by the market PDA.
+pub fn initialize_token_market(
+    ctx: Context<InitializeTokenMarket>,
+    battle_id: u64,
+    initial_liquidity: u64,
+    config: Option<MarketConfig>,
+) -> Result<()> {
+    let config = config.unwrap_or(ctx.accounts.template.params.market_config);
+    // Batch escrow only handles lamports.
+    require!(!config.batch_mode, GameError::UnsupportedForTokenMarket);
+
+    let market = &mut ctx.accounts.market_state;
+    init_market_state(
+        market,
+        &ctx.accounts.battle_state,
+        initial_liquidity,
+        config,
+        ctx.accounts.arena.trade_fee_bps,
+        &ctx.accounts.protocol_config.params,
+        ctx.bumps.market_state,
+    )?;
+    market.token_mint = ctx.accounts.token_mint.key();
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.provider_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.market_vault.to_account_info(),
+            authority: ctx.accounts.liquidity_provider.to_account_info(),
+        },
+    );
+    transfer_checked(cpi_context, initial_liquidity, ctx.accounts.token_mint.decimals)?;
+
+    let lp_position = &mut ctx.accounts.lp_position;
+    lp_position.owner = ctx.accounts.liquidity_provider.key();
+    lp_position.battle_id = battle_id;
+    lp_position.arena = ctx.accounts.arena.key();
+    lp_position.shares = initial_liquidity;
+    lp_position.deposited = initial_liquidity;
+    lp_position.bump = ctx.bumps.lp_position;
+
+    msg!(
+        "Token market initialized for battle {} with {} of {}",
+        battle_id,
+        initial_liquidity,
+        ctx.accounts.token_mint.key()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(battle_id: u64)]
+pub struct InitializeTokenMarket<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::BattleCreator) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"arena", arena.name.as_bytes()],
+        bump = arena.bump,
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"battle", arena.key().as_ref(), battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"template", battle_state.template_id.to_le_bytes().as_ref()],
+        bump = template.bump,
+    )]
+    pub template: Account<'info, BattleTemplate>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = MarketState::LEN,
+        seeds = [b"market", arena.key().as_ref(), battle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = LpPosition::LEN,
+        seeds = [
+            b"lp_position",
+            arena.key().as_ref(),
+            battle_id.to_le_bytes().as_ref(),
+            liquidity_provider.key().as_ref()
+        ],
+        bump
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"market_vault", arena.key().as_ref(), battle_id.to_le_bytes().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = market_state,
+        token::token_program = token_program,
+    )]
+    pub market_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = liquidity_provider,
+        token::token_program = token_program,
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub liquidity_provider: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
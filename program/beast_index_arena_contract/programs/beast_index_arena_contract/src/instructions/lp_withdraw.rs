@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{BattleState, GlobalState, LpPosition, MarketState};
+use crate::errors::GameError;
+use crate::funds::{available_funds, send_payout, MarketTokenAccounts};
+use crate::utils::get_lp_redemption;
+
+/// Redeems LP shares in a finished market for their cut of the deposits plus
+/// the losing pools, keeping back whatever is still owed to bettors. LP
+/// capital doesn't expire with the claim window; the market stays open until
+/// it's all out.
+pub fn lp_withdraw(ctx: Context<LpWithdraw>) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let market = &mut ctx.accounts.market_state;
+    let lp_position = &mut ctx.accounts.lp_position;
+
+    require!(battle.is_battle_over, GameError::BattleNotOver);
+    require!(lp_position.shares > 0, GameError::NoLpShares);
+
+    let tokens = MarketTokenAccounts::load(
+        market,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.owner_token_account,
+        &ctx.accounts.market_vault,
+        &ctx.accounts.token_program,
+    )?;
+    let payout = get_lp_redemption(
+        market,
+        battle,
+        available_funds(market, tokens.as_ref())?,
+        lp_position.shares,
+    )?;
+
+    send_payout(
+        market,
+        &ctx.accounts.owner.to_account_info(),
+        tokens.as_ref(),
+        payout,
+    )?;
+
+    market.total_lp_shares -= lp_position.shares;
+
+    msg!(
+        "{} redeemed {} LP shares for {} lamports (deposited {})",
+        ctx.accounts.owner.key(),
+        lp_position.shares,
+        payout,
+        lp_position.deposited
+    );
+
+    lp_position.shares = 0;
+    lp_position.deposited = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LpWithdraw<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"lp_position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            owner.key().as_ref()
+        ],
+        bump = lp_position.bump,
+        has_one = owner,
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub owner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
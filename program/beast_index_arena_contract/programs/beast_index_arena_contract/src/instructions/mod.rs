@@ -1,17 +1,131 @@
 pub mod initialize_global;
-pub mod update_current_battle;
 pub mod initialize_battle;
 pub mod initialize_market;
+pub mod initialize_token_market;
 pub mod execute_turn;
 pub mod place_bet;
 pub mod sell_shares;
 pub mod claim_winnings;
+pub mod pause_battle;
+pub mod resume_battle;
+pub mod cancel_battle;
+pub mod claim_refund;
+pub mod get_lock_status;
+pub mod queue_bet;
+pub mod queue_sell;
+pub mod cancel_batch_orders;
+pub mod settle_batch_orders;
+pub mod lp_withdraw;
+pub mod add_liquidity;
+pub mod initialize_house_vault;
+pub mod deposit_to_vault;
+pub mod withdraw_from_vault;
+pub mod vault_add_liquidity;
+pub mod vault_lp_withdraw;
+pub mod initialize_outcome_mints;
+pub mod buy_outcome_tokens;
+pub mod sell_outcome_tokens;
+pub mod redeem_outcome_tokens;
+pub mod transfer_position;
+pub mod swap_position;
+pub mod place_basket_bet;
+pub mod initialize_exotic_market;
+pub mod place_exotic_bet;
+pub mod settle_exotic_market;
+pub mod claim_exotic_winnings;
+pub mod initialize_over_under_market;
+pub mod place_limit_order;
+pub mod cancel_limit_order;
+pub mod close_position;
+pub mod close_market;
+pub mod close_battle;
+pub mod set_treasury;
+pub mod create_round;
+pub mod grant_role;
+pub mod revoke_role;
+pub mod propose_authority;
+pub mod accept_authority;
+pub mod cancel_authority_proposal;
+pub mod propose_battle_authority;
+pub mod accept_battle_authority;
+pub mod cancel_battle_authority_proposal;
+pub mod initialize_config;
+pub mod update_config;
+pub mod set_global_pause;
+pub mod create_arena;
+pub mod update_arena;
+pub mod create_template;
+pub mod update_template;
+pub mod settle_limit_order;
+pub mod close_order_queue;
+pub mod close_order_book;
+pub mod close_lp_position;
+pub mod close_vault_position;
+pub mod close_exotic_market;
+pub mod close_exotic_ticket;
 
 pub use initialize_global::*;
-pub use update_current_battle::*;
 pub use initialize_battle::*;
 pub use initialize_market::*;
+pub use initialize_token_market::*;
 pub use execute_turn::*;
 pub use place_bet::*;
 pub use sell_shares::*;
 pub use claim_winnings::*;
+pub use pause_battle::*;
+pub use resume_battle::*;
+pub use cancel_battle::*;
+pub use claim_refund::*;
+pub use get_lock_status::*;
+pub use queue_bet::*;
+pub use queue_sell::*;
+pub use cancel_batch_orders::*;
+pub use settle_batch_orders::*;
+pub use lp_withdraw::*;
+pub use add_liquidity::*;
+pub use initialize_house_vault::*;
+pub use deposit_to_vault::*;
+pub use withdraw_from_vault::*;
+pub use vault_add_liquidity::*;
+pub use vault_lp_withdraw::*;
+pub use initialize_outcome_mints::*;
+pub use buy_outcome_tokens::*;
+pub use sell_outcome_tokens::*;
+pub use redeem_outcome_tokens::*;
+pub use transfer_position::*;
+pub use swap_position::*;
+pub use place_basket_bet::*;
+pub use initialize_exotic_market::*;
+pub use place_exotic_bet::*;
+pub use settle_exotic_market::*;
+pub use claim_exotic_winnings::*;
+pub use initialize_over_under_market::*;
+pub use place_limit_order::*;
+pub use cancel_limit_order::*;
+pub use close_position::*;
+pub use close_market::*;
+pub use close_battle::*;
+pub use set_treasury::*;
+pub use create_round::*;
+pub use grant_role::*;
+pub use revoke_role::*;
+pub use propose_authority::*;
+pub use accept_authority::*;
+pub use cancel_authority_proposal::*;
+pub use propose_battle_authority::*;
+pub use accept_battle_authority::*;
+pub use cancel_battle_authority_proposal::*;
+pub use initialize_config::*;
+pub use update_config::*;
+pub use set_global_pause::*;
+pub use create_arena::*;
+pub use update_arena::*;
+pub use create_template::*;
+pub use update_template::*;
+pub use settle_limit_order::*;
+pub use close_order_queue::*;
+pub use close_order_book::*;
+pub use close_lp_position::*;
+pub use close_vault_position::*;
+pub use close_exotic_market::*;
+pub use close_exotic_ticket::*;
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, Role};
+use crate::errors::GameError;
+
+/// Either the battle's authority or a pauser can pause a live battle.
+pub fn pause_battle(ctx: Context<PauseBattle>) -> Result<()> {
+    let battle = &mut ctx.accounts.battle_state;
+    let clock = Clock::get()?;
+
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+
+    battle.is_paused = true;
+    battle.paused_at = clock.unix_timestamp;
+
+    msg!("Battle {} paused", battle.battle_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PauseBattle<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        constraint = battle_state.authority == authority.key()
+            || global_state.has_role(&authority.key(), Role::Pauser) @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    pub authority: Signer<'info>,
+}
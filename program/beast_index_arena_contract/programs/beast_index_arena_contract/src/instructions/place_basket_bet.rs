@@ -0,0 +1,258 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{BattleState, GlobalState, MarketState, OrderBook, ProtocolConfig, UserPosition};
+use crate::errors::GameError;
+use crate::funds::{collect_payment, crank_after_trade, MarketTokenAccounts};
+use crate::utils::{calculate_buy_shares, check_buy_limits, get_lock_reason};
+
+/// Splits one deposit across several creatures by weight. Every weighted leg
+/// must clear the minimum bet and land on a living creature, otherwise the
+/// whole basket fails.
+pub fn place_basket_bet(ctx: Context<PlaceBasketBet>, weights: [u16; 4], amount: u64) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let market = &ctx.accounts.market_state;
+
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    require!(!market.tokenized_shares, GameError::SharesAreTokenized);
+    require!(!market.config.batch_mode, GameError::BatchModeActive);
+
+    let clock = Clock::get()?;
+    if let Some(reason) = get_lock_reason(&market.config, battle, clock.unix_timestamp) {
+        msg!("Bets are locked: {:?}", reason);
+        return Err(GameError::BetsLocked.into());
+    }
+
+    require!(
+        market.config.max_bet == 0 || amount <= market.config.max_bet,
+        GameError::BetTooLarge
+    );
+    let protocol_config = &ctx.accounts.protocol_config;
+    protocol_config.check_bet(amount)?;
+
+    let legs = split_by_weight(amount, &weights)?;
+    for (i, leg) in legs.iter().enumerate() {
+        if *leg > 0 {
+            require!(*leg >= protocol_config.params.min_bet, GameError::BetTooSmall);
+            require!(battle.is_alive[i], GameError::CreatureIsDead);
+        }
+    }
+
+    let tokens = MarketTokenAccounts::load(
+        market,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.market_vault,
+        &ctx.accounts.token_program,
+    )?;
+    collect_payment(
+        market,
+        &ctx.accounts.user,
+        &ctx.accounts.system_program,
+        tokens.as_ref(),
+        amount,
+    )?;
+
+    let user = ctx.accounts.user.key();
+    let battle_id = battle.battle_id;
+    let arena = battle.arena;
+    let market = &mut ctx.accounts.market_state;
+    let positions = [
+        (&mut ctx.accounts.position_0, ctx.bumps.position_0),
+        (&mut ctx.accounts.position_1, ctx.bumps.position_1),
+        (&mut ctx.accounts.position_2, ctx.bumps.position_2),
+        (&mut ctx.accounts.position_3, ctx.bumps.position_3),
+    ];
+
+    for (i, (position, bump)) in positions.into_iter().enumerate() {
+        let leg = legs[i];
+        if leg == 0 {
+            continue;
+        }
+        let creature_index = i as u8;
+        let position = position.as_mut().ok_or(GameError::MissingPositionAccount)?;
+
+        let current_shares = market.creature_shares(creature_index)?;
+        let (net_leg, _) = market.split_trade_fee(leg)?;
+        let shares_bought = calculate_buy_shares(current_shares, net_leg, market.k_constant)?;
+        check_buy_limits(
+            market,
+            leg,
+            current_shares,
+            shares_bought,
+            position.amount.saturating_add(shares_bought),
+        )?;
+        market.record_buy(creature_index, net_leg, shares_bought)?;
+        market.add_cost_basis(creature_index, net_leg)?;
+
+        if position.user == Pubkey::default() {
+            position.user = user;
+            position.battle_id = battle_id;
+            position.arena = arena;
+            position.creature_index = creature_index;
+            position.amount = 0;
+            position.bump = bump.ok_or(GameError::MissingPositionAccount)?;
+            position.cost_basis = 0;
+        }
+        position.claimed = false;
+        position.add_shares(shares_bought, net_leg)?;
+
+        msg!(
+            "{} bought {} shares of Creature {} for {} lamports",
+            user,
+            shares_bought,
+            creature_index,
+            leg
+        );
+    }
+
+    crank_after_trade(market, ctx.accounts.order_book.as_mut(), battle, clock.unix_timestamp)?;
+
+    Ok(())
+}
+
+/// Field bet: an equal split across every living creature except `against`.
+pub fn place_field_bet(ctx: Context<PlaceBasketBet>, against: u8, amount: u64) -> Result<()> {
+    require!(against < 4, GameError::InvalidCreatureIndex);
+
+    let mut weights = [0u16; 4];
+    for (i, weight) in weights.iter_mut().enumerate() {
+        if i != against as usize && ctx.accounts.battle_state.is_alive[i] {
+            *weight = 1;
+        }
+    }
+
+    place_basket_bet(ctx, weights, amount)
+}
+
+/// Splits `amount` pro rata to `weights`. Rounding dust goes to the last
+/// weighted leg so the legs always sum to `amount`.
+fn split_by_weight(amount: u64, weights: &[u16; 4]) -> Result<[u64; 4]> {
+    let total_weight: u64 = weights.iter().map(|w| *w as u64).sum();
+    require!(total_weight > 0, GameError::EmptyBasket);
+
+    let mut legs = [0u64; 4];
+    let mut remaining = amount;
+    let last = weights.iter().rposition(|w| *w > 0).unwrap_or(0);
+    for (i, weight) in weights.iter().enumerate() {
+        if *weight == 0 {
+            continue;
+        }
+        let leg = if i == last {
+            remaining
+        } else {
+            ((amount as u128) * (*weight as u128) / (total_weight as u128)) as u64
+        };
+        legs[i] = leg;
+        remaining -= leg;
+    }
+
+    Ok(legs)
+}
+
+#[derive(Accounts)]
+pub struct PlaceBasketBet<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    /// Resting limit orders crossed by this trade fill right after it.
+    /// Required once the battle has a book.
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Option<Account<'info, OrderBook>>,
+
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Positions are only needed for the creatures the basket covers.
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::LEN,
+        seeds = [b"position", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), user.key().as_ref(), &[0]],
+        bump
+    )]
+    pub position_0: Option<Account<'info, UserPosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::LEN,
+        seeds = [b"position", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), user.key().as_ref(), &[1]],
+        bump
+    )]
+    pub position_1: Option<Account<'info, UserPosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::LEN,
+        seeds = [b"position", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), user.key().as_ref(), &[2]],
+        bump
+    )]
+    pub position_2: Option<Account<'info, UserPosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::LEN,
+        seeds = [b"position", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), user.key().as_ref(), &[3]],
+        bump
+    )]
+    pub position_3: Option<Account<'info, UserPosition>>,
+
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_keeps_dust_on_last_leg() {
+        let legs = split_by_weight(100_000_001, &[1, 0, 1, 1]).unwrap();
+        assert_eq!(legs, [33_333_333, 0, 33_333_333, 33_333_335]);
+        assert!(split_by_weight(100, &[0; 4]).is_err());
+    }
+}
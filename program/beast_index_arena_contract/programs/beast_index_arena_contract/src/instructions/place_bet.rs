@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program::{transfer, Transfer};
-use crate::state::{BattleState, MarketState, UserPosition};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{BattleState, GlobalState, MarketState, OrderBook, ProtocolConfig, UserPosition};
 use crate::errors::GameError;
-use crate::utils::{calculate_buy_shares, get_share_price};
+use crate::funds::{collect_payment, crank_after_trade, MarketTokenAccounts};
+use crate::utils::{calculate_buy_shares, check_buy_limits, get_lock_reason, get_share_price};
 
 pub fn place_bet(
     ctx: Context<PlaceBet>,
@@ -15,91 +16,80 @@ pub fn place_bet(
 
     require!(creature_index < 4, GameError::InvalidCreatureIndex);
     require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
-    require!(amount >= 10_000_000, GameError::BetTooSmall);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    require!(!market.tokenized_shares, GameError::SharesAreTokenized);
+    require!(!market.config.batch_mode, GameError::BatchModeActive);
+    ctx.accounts.protocol_config.check_bet(amount)?;
     require!(
         battle.is_alive[creature_index as usize],
         GameError::CreatureIsDead
     );
 
-    let current_shares = match creature_index {
-        0 => market.creature_0_shares,
-        1 => market.creature_1_shares,
-        2 => market.creature_2_shares,
-        3 => market.creature_3_shares,
-        _ => return Err(GameError::InvalidCreatureIndex.into()),
-    };
-    let shares_bought = calculate_buy_shares(current_shares, amount, market.k_constant)?;
-
-    let cpi_context = CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.user.to_account_info(),
-            to: market.to_account_info(),
-        },
-    );
-    transfer(cpi_context, amount)?;
-    match creature_index {
-        0 => {
-            market.creature_0_pool += amount;
-            market.creature_0_shares -= shares_bought;
-        }
-        1 => {
-            market.creature_1_pool += amount;
-            market.creature_1_shares -= shares_bought;
-        }
-        2 => {
-            market.creature_2_pool += amount;
-            market.creature_2_shares -= shares_bought;
-        }
-        3 => {
-            market.creature_3_pool += amount;
-            market.creature_3_shares -= shares_bought;
-        }
-        _ => return Err(GameError::InvalidCreatureIndex.into()),
+    let clock = Clock::get()?;
+    if let Some(reason) = get_lock_reason(&market.config, battle, clock.unix_timestamp) {
+        msg!("Bets are locked: {:?}", reason);
+        return Err(GameError::BetsLocked.into());
     }
 
-    market.total_pool += amount;
+    let current_shares = market.creature_shares(creature_index)?;
+    let (net_amount, fee) = market.split_trade_fee(amount)?;
+    let shares_bought = calculate_buy_shares(current_shares, net_amount, market.k_constant)?;
+    check_buy_limits(
+        market,
+        amount,
+        current_shares,
+        shares_bought,
+        position.amount.saturating_add(shares_bought),
+    )?;
+
+    let tokens = MarketTokenAccounts::load(
+        market,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.market_vault,
+        &ctx.accounts.token_program,
+    )?;
+    collect_payment(
+        market,
+        &ctx.accounts.user,
+        &ctx.accounts.system_program,
+        tokens.as_ref(),
+        amount,
+    )?;
+
+    market.record_buy(creature_index, net_amount, shares_bought)?;
+    market.add_cost_basis(creature_index, net_amount)?;
 
     if position.user == Pubkey::default() {
         position.user = ctx.accounts.user.key();
         position.battle_id = battle.battle_id;
+        position.arena = battle.arena;
         position.creature_index = creature_index;
-        position.amount = shares_bought;
+        position.amount = 0;
         position.claimed = false;
         position.bump = ctx.bumps.user_position;
-    } else {
-        position.amount = position
-            .amount
-            .checked_add(shares_bought)
-            .ok_or(GameError::CalculationOverflow)?;
+        position.cost_basis = 0;
     }
+    position.add_shares(shares_bought, net_amount)?;
 
     msg!(
-        "{} bought {} shares of Creature {} for {} lamports",
+        "{} bought {} shares of Creature {} for {} lamports ({} fee)",
         ctx.accounts.user.key(),
         shares_bought,
         creature_index,
-        amount
+        amount,
+        fee
     );
 
     let current_price = get_share_price(
-        match creature_index {
-            0 => market.creature_0_pool,
-            1 => market.creature_1_pool,
-            2 => market.creature_2_pool,
-            3 => market.creature_3_pool,
-            _ => 0,
-        },
-        match creature_index {
-            0 => market.creature_0_shares,
-            1 => market.creature_1_shares,
-            2 => market.creature_2_shares,
-            3 => market.creature_3_shares,
-            _ => 0,
-        },
+        market.creature_pool(creature_index)?,
+        market.creature_shares(creature_index)?,
+        market.price_scale,
     )?;
     msg!("Current price per share: {}", current_price);
 
+    crank_after_trade(market, ctx.accounts.order_book.as_mut(), battle, clock.unix_timestamp)?;
+
     Ok(())
 }
 
@@ -108,22 +98,36 @@ pub fn place_bet(
 pub struct PlaceBet<'info> {
     #[account(
         mut,
-        seeds = [b"market", battle_state.battle_id.to_le_bytes().as_ref()],
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
         bump = market_state.bump,
     )]
     pub market_state: Account<'info, MarketState>,
 
     #[account(
-        seeds = [b"battle", battle_state.battle_id.to_le_bytes().as_ref()],
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
         bump = battle_state.bump,
     )]
     pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
     #[account(
         init_if_needed,
         payer = user,
         space = UserPosition::LEN,
         seeds = [
             b"position",
+            battle_state.arena.as_ref(),
             battle_state.battle_id.to_le_bytes().as_ref(),
             user.key().as_ref(),
             &[creature_index]
@@ -131,6 +135,30 @@ pub struct PlaceBet<'info> {
         bump
     )]
     pub user_position: Account<'info, UserPosition>,
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Resting limit orders crossed by this trade fill right after it.
+    /// Required once the battle has a book.
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Option<Account<'info, OrderBook>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 
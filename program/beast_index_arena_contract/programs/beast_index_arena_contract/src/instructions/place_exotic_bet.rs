@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{
+    BattleState, ExoticKind, ExoticMarket, ExoticTicket, GlobalState, ProtocolConfig,
+};
+use crate::errors::GameError;
+use crate::utils::encode_selection;
+
+/// Stakes `amount` on a pick in the given round. Finishing-order and first-blood
+/// markets close at the first death, over/under at the first turn; the
+/// next-death market only takes bets on the round that is currently open.
+pub fn place_exotic_bet(
+    ctx: Context<PlaceExoticBet>,
+    round: u8,
+    selection: [u8; 3],
+    amount: u64,
+) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let exotic = &mut ctx.accounts.exotic_market;
+    let ticket = &mut ctx.accounts.ticket;
+
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    ctx.accounts.protocol_config.check_bet(amount)?;
+
+    let is_open = match exotic.kind {
+        ExoticKind::NextDeath => round == battle.death_count,
+        ExoticKind::OverUnder => round == 0 && battle.current_turn == 0,
+        _ => round == 0 && battle.death_count == 0,
+    };
+    require!(is_open, GameError::ExoticBettingClosed);
+
+    let outcome = encode_selection(exotic.kind, selection)?;
+    if matches!(exotic.kind, ExoticKind::FirstBlood | ExoticKind::NextDeath) {
+        require!(
+            battle.is_alive[selection[0] as usize],
+            GameError::CreatureIsDead
+        );
+    }
+    let index = exotic.pool_index(round, outcome)?;
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.user.to_account_info(),
+            to: exotic.to_account_info(),
+        },
+    );
+    transfer(cpi_context, amount)?;
+
+    exotic.pools[index] = exotic.pools[index]
+        .checked_add(amount)
+        .ok_or(GameError::CalculationOverflow)?;
+    exotic.round_totals[round as usize] = exotic.round_totals[round as usize]
+        .checked_add(amount)
+        .ok_or(GameError::CalculationOverflow)?;
+
+    if ticket.user == Pubkey::default() {
+        ticket.user = ctx.accounts.user.key();
+        ticket.battle_id = battle.battle_id;
+        ticket.arena = battle.arena;
+        ticket.kind = exotic.kind;
+        ticket.round = round;
+        ticket.outcome = outcome;
+        ticket.amount = 0;
+        ticket.claimed = false;
+        ticket.bump = ctx.bumps.ticket;
+    }
+    ticket.amount = ticket
+        .amount
+        .checked_add(amount)
+        .ok_or(GameError::CalculationOverflow)?;
+
+    msg!(
+        "{} staked {} on {:?} outcome {} (round {})",
+        ctx.accounts.user.key(),
+        amount,
+        exotic.kind,
+        outcome,
+        round
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round: u8, selection: [u8; 3])]
+pub struct PlaceExoticBet<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"exotic", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[exotic_market.kind as u8]],
+        bump = exotic_market.bump,
+    )]
+    pub exotic_market: Account<'info, ExoticMarket>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = ExoticTicket::LEN,
+        seeds = [
+            b"exotic_ticket",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            &[exotic_market.kind as u8, round],
+            user.key().as_ref(),
+            selection.as_ref()
+        ],
+        bump
+    )]
+    pub ticket: Account<'info, ExoticTicket>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
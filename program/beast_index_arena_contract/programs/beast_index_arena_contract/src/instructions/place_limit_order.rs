@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{
+    BattleState, GlobalState, LimitOrder, MarketState, OrderBook, ProtocolConfig, UserPosition,
+};
+use crate::errors::GameError;
+use crate::funds::crank_order_book;
+use crate::utils::calculate_sell_price;
+
+/// Posts a resting order at `limit_price` (lamports per 1e9 shares). A buy
+/// escrows `amount` lamports in the book; a sell escrows `amount` shares out
+/// of the signer's position. Whatever already crosses fills right away.
+pub fn place_limit_order(
+    ctx: Context<PlaceLimitOrder>,
+    creature_index: u8,
+    is_buy: bool,
+    limit_price: u64,
+    amount: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market_state;
+    let battle = &mut ctx.accounts.battle_state;
+    let book = &mut ctx.accounts.order_book;
+    let position = &mut ctx.accounts.user_position;
+
+    require!(creature_index < 4, GameError::InvalidCreatureIndex);
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    require!(
+        market.token_mint == Pubkey::default(),
+        GameError::UnsupportedForTokenMarket
+    );
+    require!(!market.tokenized_shares, GameError::SharesAreTokenized);
+    require!(!market.config.batch_mode, GameError::BatchModeActive);
+    require!(limit_price > 0, GameError::ZeroAmount);
+    require!(
+        book.orders.len() < OrderBook::MAX_ORDERS,
+        GameError::OrderBookFull
+    );
+
+    book.battle_id = battle.battle_id;
+    if book.payer == Pubkey::default() {
+        book.payer = ctx.accounts.user.key();
+    }
+    book.bump = ctx.bumps.order_book;
+    battle.has_order_book = true;
+
+    if position.user == Pubkey::default() {
+        position.user = ctx.accounts.user.key();
+        position.battle_id = battle.battle_id;
+        position.arena = battle.arena;
+        position.creature_index = creature_index;
+        position.amount = 0;
+        position.claimed = false;
+        position.bump = ctx.bumps.user_position;
+        position.cost_basis = 0;
+    }
+
+    let position_at_post = position.amount;
+    let cost_basis = if is_buy {
+        ctx.accounts.protocol_config.check_bet(amount)?;
+        require!(
+            market.config.max_bet == 0 || amount <= market.config.max_bet,
+            GameError::BetTooLarge
+        );
+        require!(
+            battle.is_alive[creature_index as usize],
+            GameError::CreatureIsDead
+        );
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: book.to_account_info(),
+            },
+        );
+        transfer(cpi_context, amount)?;
+        0
+    } else {
+        require!(amount > 0, GameError::ZeroAmount);
+        require!(!position.claimed, GameError::AlreadyClaimed);
+        require!(amount <= position.amount, GameError::InsufficientShares);
+        // Sells have to be worth a minimum bet too, so the book can't be
+        // filled with dust.
+        let current_shares = market.creature_shares(creature_index)?;
+        let value = calculate_sell_price(current_shares, amount, market.k_constant)?;
+        require!(
+            value >= ctx.accounts.protocol_config.params.min_bet,
+            GameError::OrderTooSmall
+        );
+        position.remove_shares(amount)?
+    };
+
+    let order_id = book.next_order_id;
+    book.next_order_id += 1;
+    book.orders.push(LimitOrder {
+        id: order_id,
+        owner: ctx.accounts.user.key(),
+        creature_index,
+        is_buy,
+        limit_price,
+        remaining: amount,
+        filled: 0,
+        cost_basis,
+        position_at_post,
+    });
+
+    msg!(
+        "{} posted limit {} #{} on Creature {}: {} at {}",
+        ctx.accounts.user.key(),
+        if is_buy { "buy" } else { "sell" },
+        order_id,
+        creature_index,
+        amount,
+        limit_price
+    );
+
+    let clock = Clock::get()?;
+    crank_order_book(market, book, battle, clock.unix_timestamp)
+}
+
+#[derive(Accounts)]
+#[instruction(creature_index: u8)]
+pub struct PlaceLimitOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    /// Flagged so trades and turns can't skip cranking the book.
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = OrderBook::LEN,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::LEN,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[creature_index]
+        ],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
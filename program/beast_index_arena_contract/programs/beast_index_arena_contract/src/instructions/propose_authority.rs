@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::state::GlobalState;
+use crate::errors::GameError;
+
+/// First step of handing over the global authority. Nothing changes until the
+/// proposed key signs `accept_authority`, so a wrong key can't lock the program.
+pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    require!(new_authority != Pubkey::default(), GameError::InvalidAuthority);
+
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.pending_authority = new_authority;
+    msg!("Global authority transfer to {} proposed", new_authority);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::state::BattleState;
+use crate::errors::GameError;
+
+/// First step of handing a battle to another key; see `propose_authority`.
+pub fn propose_battle_authority(
+    ctx: Context<ProposeBattleAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    require!(new_authority != Pubkey::default(), GameError::InvalidAuthority);
+
+    let battle = &mut ctx.accounts.battle_state;
+    battle.pending_authority = new_authority;
+    msg!("Battle {} authority transfer to {} proposed", battle.battle_id, new_authority);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeBattleAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{
+    BatchOrder, BattleState, GlobalState, MarketState, OrderQueue, ProtocolConfig, UserPosition,
+};
+use crate::errors::GameError;
+use crate::utils::{calculate_buy_shares, check_buy_limits, get_lock_reason};
+
+pub fn queue_bet(ctx: Context<QueueBet>, creature_index: u8, amount: u64) -> Result<()> {
+    let market = &ctx.accounts.market_state;
+    let battle = &mut ctx.accounts.battle_state;
+    let queue = &mut ctx.accounts.order_queue;
+    let position = &mut ctx.accounts.user_position;
+
+    require!(market.config.batch_mode, GameError::BatchModeDisabled);
+    require!(creature_index < 4, GameError::InvalidCreatureIndex);
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    ctx.accounts.protocol_config.check_bet(amount)?;
+    require!(
+        battle.is_alive[creature_index as usize],
+        GameError::CreatureIsDead
+    );
+    require!(
+        queue.orders.len() < OrderQueue::MAX_ORDERS,
+        GameError::OrderQueueFull
+    );
+
+    let clock = Clock::get()?;
+    if let Some(reason) = get_lock_reason(&market.config, battle, clock.unix_timestamp) {
+        msg!("Bets are locked: {:?}", reason);
+        return Err(GameError::BetsLocked.into());
+    }
+
+    // The fill is only known once the batch clears, so the market's limits are
+    // checked against the curve as it stands now.
+    let current_shares = market.creature_shares(creature_index)?;
+    let (net_amount, _) = market.split_trade_fee(amount)?;
+    let estimated_shares = calculate_buy_shares(current_shares, net_amount, market.k_constant)?;
+    check_buy_limits(
+        market,
+        amount,
+        current_shares,
+        estimated_shares,
+        position.amount.saturating_add(estimated_shares),
+    )?;
+
+    queue.battle_id = battle.battle_id;
+    if queue.payer == Pubkey::default() {
+        queue.payer = ctx.accounts.user.key();
+    }
+    queue.bump = ctx.bumps.order_queue;
+    battle.has_order_queue = true;
+
+    // Lamports sit in the queue until the batch clears.
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.user.to_account_info(),
+            to: queue.to_account_info(),
+        },
+    );
+    transfer(cpi_context, amount)?;
+
+    queue.orders.push(BatchOrder {
+        user: ctx.accounts.user.key(),
+        creature_index,
+        is_buy: true,
+        amount,
+        fill: 0,
+        is_cleared: false,
+        cost_basis: 0,
+    });
+
+    if position.user == Pubkey::default() {
+        position.user = ctx.accounts.user.key();
+        position.battle_id = battle.battle_id;
+        position.arena = battle.arena;
+        position.creature_index = creature_index;
+        position.amount = 0;
+        position.claimed = false;
+        position.bump = ctx.bumps.user_position;
+    }
+
+    msg!(
+        "{} queued a {} lamport bet on Creature {} for batch {}",
+        ctx.accounts.user.key(),
+        amount,
+        creature_index,
+        queue.batch_number
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(creature_index: u8, amount: u64)]
+pub struct QueueBet<'info> {
+    #[account(
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    /// Flagged so turns can't skip clearing the queue.
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = OrderQueue::LEN,
+        seeds = [b"order_queue", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub order_queue: Account<'info, OrderQueue>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::LEN,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[creature_index]
+        ],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    BatchOrder, BattleState, GlobalState, MarketState, OrderQueue, ProtocolConfig, UserPosition,
+};
+use crate::errors::GameError;
+use crate::utils::{calculate_sell_price, get_lock_status};
+
+pub fn queue_sell(ctx: Context<QueueSell>, shares_to_sell: u64) -> Result<()> {
+    let market = &ctx.accounts.market_state;
+    let battle = &mut ctx.accounts.battle_state;
+    let queue = &mut ctx.accounts.order_queue;
+    let position = &mut ctx.accounts.user_position;
+
+    require!(market.config.batch_mode, GameError::BatchModeDisabled);
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    require!(
+        shares_to_sell > 0 && shares_to_sell <= position.amount,
+        GameError::InsufficientShares
+    );
+    require!(
+        queue.orders.len() < OrderQueue::MAX_ORDERS,
+        GameError::OrderQueueFull
+    );
+    // Every order has to be worth a minimum bet, so the queue can't be filled
+    // with dust.
+    let current_shares = market.creature_shares(position.creature_index)?;
+    let value = calculate_sell_price(current_shares, shares_to_sell, market.k_constant)?;
+    require!(
+        value >= ctx.accounts.protocol_config.params.min_bet,
+        GameError::OrderTooSmall
+    );
+
+    let clock = Clock::get()?;
+    let lock_status = get_lock_status(&market.config, battle, clock.unix_timestamp);
+    if lock_status.sells_locked {
+        msg!("Sells are locked: {:?}", lock_status.reason);
+        return Err(GameError::SellsLocked.into());
+    }
+
+    queue.battle_id = battle.battle_id;
+    if queue.payer == Pubkey::default() {
+        queue.payer = ctx.accounts.user.key();
+    }
+    queue.bump = ctx.bumps.order_queue;
+    battle.has_order_queue = true;
+
+    // Shares are escrowed by taking them off the position until the batch clears.
+    let cost_basis = position.remove_shares(shares_to_sell)?;
+
+    queue.orders.push(BatchOrder {
+        user: ctx.accounts.user.key(),
+        creature_index: position.creature_index,
+        is_buy: false,
+        amount: shares_to_sell,
+        fill: 0,
+        is_cleared: false,
+        cost_basis,
+    });
+
+    msg!(
+        "{} queued a sell of {} shares of Creature {} for batch {}",
+        ctx.accounts.user.key(),
+        shares_to_sell,
+        position.creature_index,
+        queue.batch_number
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct QueueSell<'info> {
+    #[account(
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    /// Flagged so turns can't skip clearing the queue.
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = OrderQueue::LEN,
+        seeds = [b"order_queue", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub order_queue: Account<'info, OrderQueue>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[user_position.creature_index]
+        ],
+        bump = user_position.bump,
+        has_one = user,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+use crate::state::{BattleState, GlobalState, MarketState};
+use crate::errors::GameError;
+use crate::funds::{available_funds, send_payout, MarketTokenAccounts};
+use crate::utils::get_outcome_token_refund;
+
+/// Burns the caller's whole outcome-token balance for its payout: the winner's
+/// share of the pool after settlement, or a pro-rata refund after a cancel.
+pub fn redeem_outcome_tokens(ctx: Context<RedeemOutcomeTokens>, creature_index: u8) -> Result<()> {
+    let market = &mut ctx.accounts.market_state;
+    let battle = &ctx.accounts.battle_state;
+    let now = Clock::get()?.unix_timestamp;
+    let balance = ctx.accounts.user_outcome_account.amount;
+
+    require!(creature_index < 4, GameError::InvalidCreatureIndex);
+    require!(market.tokenized_shares, GameError::SharesNotTokenized);
+    require!(balance > 0, GameError::InsufficientShares);
+    require!(
+        !battle.is_battle_over
+            || now <= battle.claim_deadline(market.config.claim_window_secs, &ctx.accounts.global_state, now),
+        GameError::ClaimWindowExpired
+    );
+
+    let tokens = MarketTokenAccounts::load(
+        market,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.market_vault,
+        &ctx.accounts.token_program,
+    )?;
+
+    let payout = if market.is_refunding(battle) {
+        let refund = get_outcome_token_refund(market, creature_index, balance)?;
+        market.record_sell(creature_index, refund, balance)?;
+        refund
+    } else {
+        require!(battle.is_battle_over, GameError::BattleNotOver);
+        let winner = battle.winner.ok_or(GameError::NoWinner)?;
+        require!(creature_index == winner, GameError::NotAWinner);
+
+        let payout = (balance as u128)
+            .checked_mul(market.total_pool as u128)
+            .ok_or(GameError::CalculationOverflow)?
+            .checked_div(market.creature_pool(winner)? as u128)
+            .ok_or(GameError::DivisionByZero)? as u64;
+        require!(
+            available_funds(market, tokens.as_ref())? >= payout,
+            GameError::MarketInsolvent
+        );
+        market.winning_shares_claimed = market
+            .winning_shares_claimed
+            .checked_add(balance)
+            .ok_or(GameError::CalculationOverflow)?;
+        payout
+    };
+
+    burn(
+        CpiContext::new(
+            ctx.accounts.outcome_token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.outcome_mint.to_account_info(),
+                from: ctx.accounts.user_outcome_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        balance,
+    )?;
+
+    send_payout(
+        market,
+        &ctx.accounts.user.to_account_info(),
+        tokens.as_ref(),
+        payout,
+    )?;
+
+    msg!(
+        "{} redeemed {} Creature {} outcome tokens for {}",
+        ctx.accounts.user.key(),
+        balance,
+        creature_index,
+        payout
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(creature_index: u8)]
+pub struct RedeemOutcomeTokens<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[creature_index]],
+        bump,
+        mint::token_program = outcome_token_program,
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = outcome_mint,
+        token::authority = user,
+        token::token_program = outcome_token_program,
+    )]
+    pub user_outcome_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub outcome_token_program: Interface<'info, TokenInterface>,
+
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
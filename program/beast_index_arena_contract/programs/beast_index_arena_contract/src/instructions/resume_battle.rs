@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, Role};
+use crate::errors::GameError;
+
+/// Either the battle's authority or a pauser can resume it.
+pub fn resume_battle(ctx: Context<ResumeBattle>) -> Result<()> {
+    let battle = &mut ctx.accounts.battle_state;
+    let clock = Clock::get()?;
+
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(battle.is_paused, GameError::BattleNotPaused);
+
+    // Shift the turn clock so the paused time doesn't count against max_duration.
+    let paused_for = clock.unix_timestamp - battle.paused_at;
+    battle.start_time += paused_for;
+    battle.last_turn_time += paused_for;
+
+    battle.is_paused = false;
+    battle.paused_at = 0;
+
+    msg!("Battle {} resumed after {} seconds", battle.battle_id, paused_for);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResumeBattle<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+        constraint = battle_state.authority == authority.key()
+            || global_state.has_role(&authority.key(), Role::Pauser) @ GameError::Unauthorized,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+use crate::state::{BattleState, GlobalState, MarketState, OrderBook};
+use crate::errors::GameError;
+use crate::funds::{crank_after_trade, send_payout, MarketTokenAccounts};
+use crate::utils::{calculate_sell_price, check_price_impact, get_lock_status};
+
+/// Burns outcome tokens back into the curve, like `sell_shares`.
+pub fn sell_outcome_tokens(
+    ctx: Context<SellOutcomeTokens>,
+    creature_index: u8,
+    shares_to_sell: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market_state;
+    let battle = &ctx.accounts.battle_state;
+
+    require!(creature_index < 4, GameError::InvalidCreatureIndex);
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    require!(market.tokenized_shares, GameError::SharesNotTokenized);
+
+    let clock = Clock::get()?;
+    let lock_status = get_lock_status(&market.config, battle, clock.unix_timestamp);
+    if lock_status.sells_locked {
+        msg!("Sells are locked: {:?}", lock_status.reason);
+        return Err(GameError::SellsLocked.into());
+    }
+
+    require!(
+        shares_to_sell <= ctx.accounts.user_outcome_account.amount,
+        GameError::InsufficientShares
+    );
+
+    let current_shares = market.creature_shares(creature_index)?;
+    check_price_impact(&market.config, current_shares, shares_to_sell)?;
+    let proceeds = calculate_sell_price(current_shares, shares_to_sell, market.k_constant)?;
+
+    burn(
+        CpiContext::new(
+            ctx.accounts.outcome_token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.outcome_mint.to_account_info(),
+                from: ctx.accounts.user_outcome_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        shares_to_sell,
+    )?;
+
+    let tokens = MarketTokenAccounts::load(
+        market,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.market_vault,
+        &ctx.accounts.token_program,
+    )?;
+    send_payout(
+        market,
+        &ctx.accounts.user.to_account_info(),
+        tokens.as_ref(),
+        proceeds,
+    )?;
+
+    market.record_sell(creature_index, proceeds, shares_to_sell)?;
+
+    msg!(
+        "{} burned {} Creature {} outcome tokens for {}",
+        ctx.accounts.user.key(),
+        shares_to_sell,
+        creature_index,
+        proceeds
+    );
+
+    crank_after_trade(market, ctx.accounts.order_book.as_mut(), battle, clock.unix_timestamp)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(creature_index: u8)]
+pub struct SellOutcomeTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    /// Resting limit orders crossed by this trade fill right after it.
+    /// Required once the battle has a book.
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Option<Account<'info, OrderBook>>,
+
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[creature_index]],
+        bump,
+        mint::token_program = outcome_token_program,
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = outcome_mint,
+        token::authority = user,
+        token::token_program = outcome_token_program,
+    )]
+    pub user_outcome_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub outcome_token_program: Interface<'info, TokenInterface>,
+
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
-use crate::state::{BattleState, MarketState, UserPosition};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{BattleState, GlobalState, MarketState, OrderBook, UserPosition};
 use crate::errors::GameError;
-use crate::utils::calculate_sell_price;
+use crate::funds::{crank_after_trade, send_payout, MarketTokenAccounts};
+use crate::utils::{calculate_sell_price, check_price_impact, get_lock_status};
 
 pub fn sell_shares(ctx: Context<SellShares>, shares_to_sell: u64) -> Result<()> {
     let market = &mut ctx.accounts.market_state;
@@ -9,6 +11,16 @@ pub fn sell_shares(ctx: Context<SellShares>, shares_to_sell: u64) -> Result<()>
     let position = &mut ctx.accounts.user_position;
 
     require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    require!(!market.tokenized_shares, GameError::SharesAreTokenized);
+    require!(!market.config.batch_mode, GameError::BatchModeActive);
+
+    let clock = Clock::get()?;
+    let lock_status = get_lock_status(&market.config, battle, clock.unix_timestamp);
+    if lock_status.sells_locked {
+        msg!("Sells are locked: {:?}", lock_status.reason);
+        return Err(GameError::SellsLocked.into());
+    }
 
     require!(
         shares_to_sell <= position.amount,
@@ -17,42 +29,29 @@ pub fn sell_shares(ctx: Context<SellShares>, shares_to_sell: u64) -> Result<()>
 
     let creature_index = position.creature_index;
 
-    let current_shares = match creature_index {
-        0 => market.creature_0_shares,
-        1 => market.creature_1_shares,
-        2 => market.creature_2_shares,
-        3 => market.creature_3_shares,
-        _ => return Err(GameError::InvalidCreatureIndex.into()),
-    };
+    let current_shares = market.creature_shares(creature_index)?;
 
+    check_price_impact(&market.config, current_shares, shares_to_sell)?;
     let sol_returned = calculate_sell_price(current_shares, shares_to_sell, market.k_constant)?;
 
-    **market.to_account_info().try_borrow_mut_lamports()? -= sol_returned;
-    **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += sol_returned;
-
-    match creature_index {
-        0 => {
-            market.creature_0_pool -= sol_returned;
-            market.creature_0_shares += shares_to_sell;
-        }
-        1 => {
-            market.creature_1_pool -= sol_returned;
-            market.creature_1_shares += shares_to_sell;
-        }
-        2 => {
-            market.creature_2_pool -= sol_returned;
-            market.creature_2_shares += shares_to_sell;
-        }
-        3 => {
-            market.creature_3_pool -= sol_returned;
-            market.creature_3_shares += shares_to_sell;
-        }
-        _ => return Err(GameError::InvalidCreatureIndex.into()),
-    }
-
-    market.total_pool -= sol_returned;
-
-    position.amount -= shares_to_sell;
+    let tokens = MarketTokenAccounts::load(
+        market,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.market_vault,
+        &ctx.accounts.token_program,
+    )?;
+    send_payout(
+        market,
+        &ctx.accounts.user.to_account_info(),
+        tokens.as_ref(),
+        sol_returned,
+    )?;
+
+    market.record_sell(creature_index, sol_returned, shares_to_sell)?;
+
+    let cost = position.remove_shares(shares_to_sell)?;
+    market.remove_cost_basis(creature_index, cost)?;
 
     if position.amount == 0 {
         position.claimed = true;
@@ -66,20 +65,29 @@ pub fn sell_shares(ctx: Context<SellShares>, shares_to_sell: u64) -> Result<()>
         sol_returned
     );
 
+    crank_after_trade(market, ctx.accounts.order_book.as_mut(), battle, clock.unix_timestamp)?;
+
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct SellShares<'info> {
     #[account(
-        seeds = [b"battle", battle_state.battle_id.to_le_bytes().as_ref()],
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
         bump = battle_state.bump,
     )]
     pub battle_state: Account<'info, BattleState>,
 
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
     #[account(
         mut,
-        seeds = [b"market", battle_state.battle_id.to_le_bytes().as_ref()],
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
         bump = market_state.bump,
     )]
     pub market_state: Account<'info, MarketState>,
@@ -88,6 +96,7 @@ pub struct SellShares<'info> {
         mut,
         seeds = [
             b"position",
+            battle_state.arena.as_ref(),
             battle_state.battle_id.to_le_bytes().as_ref(),
             user.key().as_ref(),
             &[user_position.creature_index]
@@ -97,6 +106,30 @@ pub struct SellShares<'info> {
     )]
     pub user_position: Account<'info, UserPosition>,
 
+    /// Token markets only.
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"market_vault", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Resting limit orders crossed by this trade fill right after it.
+    /// Required once the battle has a book.
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Option<Account<'info, OrderBook>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalState, Role};
+use crate::errors::GameError;
+
+/// Circuit breaker: stops trading, turns or claims across every battle at
+/// once. Each flag is set independently, so e.g. claims can stay open while
+/// trading is halted. Time spent with turns or claims paused is added back to
+/// battle clocks and claim windows.
+pub fn set_global_pause(
+    ctx: Context<SetGlobalPause>,
+    trading: bool,
+    turns: bool,
+    claims: bool,
+) -> Result<()> {
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.set_pause(trading, turns, claims, Clock::get()?.unix_timestamp);
+    msg!(
+        "Global pause set: trading {}, turns {}, claims {}",
+        trading,
+        turns,
+        claims
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetGlobalPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::Pauser) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalState, Role};
+use crate::errors::GameError;
+
+pub fn set_treasury(ctx: Context<SetTreasury>, treasury: Pubkey) -> Result<()> {
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.treasury = treasury;
+    msg!("Treasury set to {}", treasury);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTreasury<'info> {
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::TreasuryManager) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub authority: Signer<'info>,
+}
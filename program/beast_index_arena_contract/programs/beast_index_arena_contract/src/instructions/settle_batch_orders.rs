@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, MarketState, OrderQueue, UserPosition};
+use crate::errors::GameError;
+
+/// Moves a user's cleared fills for one creature out of the queue: shares onto
+/// the position for buys, lamports to the wallet for sells. Permissionless, so
+/// a crank can clear the queue out after every turn.
+pub fn settle_batch_orders(ctx: Context<SettleBatchOrders>) -> Result<()> {
+    let market = &mut ctx.accounts.market_state;
+    let queue = &mut ctx.accounts.order_queue;
+    let position = &mut ctx.accounts.user_position;
+    let user = &ctx.accounts.user;
+
+    let mut shares_filled: u64 = 0;
+    let mut shares_cost: u64 = 0;
+    let mut lamports_filled: u64 = 0;
+    let mut settled = 0;
+
+    queue.orders.retain(|order| {
+        let is_mine = order.user == user.key()
+            && order.creature_index == position.creature_index
+            && order.is_cleared;
+        if is_mine {
+            if order.is_buy {
+                shares_filled += order.fill;
+                shares_cost += order.cost_basis;
+            } else {
+                lamports_filled += order.fill;
+            }
+            settled += 1;
+        }
+        !is_mine
+    });
+
+    require!(settled > 0, GameError::NoPendingOrders);
+
+    if shares_filled > 0 {
+        position.deliver_shares(shares_filled, shares_cost)?;
+    }
+
+    market.batch_fills_owed = market.batch_fills_owed.saturating_sub(lamports_filled);
+    **market.to_account_info().try_borrow_mut_lamports()? -= lamports_filled;
+    **user.to_account_info().try_borrow_mut_lamports()? += lamports_filled;
+
+    msg!(
+        "{} settled {} orders on Creature {}: {} shares and {} lamports",
+        user.key(),
+        settled,
+        position.creature_index,
+        shares_filled,
+        lamports_filled
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleBatchOrders<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    #[account(
+        mut,
+        seeds = [b"order_queue", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_queue.bump,
+    )]
+    pub order_queue: Account<'info, OrderQueue>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[user_position.creature_index]
+        ],
+        bump = user_position.bump,
+        has_one = user,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// CHECK: The position's owner, matched by `has_one`; only receives
+    /// lamports. Anyone can settle on the owner's behalf.
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, ExoticMarket};
+use crate::utils::get_exotic_result;
+
+/// Permissionless crank: settles every round the battle has decided so far.
+/// A round nobody picked correctly refunds instead of stranding its pool.
+pub fn settle_exotic_market(ctx: Context<SettleExoticMarket>) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let exotic = &mut ctx.accounts.exotic_market;
+
+    while (exotic.settled_rounds as usize) < exotic.kind.rounds() {
+        let round = exotic.settled_rounds;
+        let Some(result) = get_exotic_result(exotic.kind, &exotic.line, round, battle)? else {
+            break;
+        };
+
+        let winning_outcome = match result {
+            Some(outcome) if exotic.pools[exotic.pool_index(round, outcome)?] > 0 => Some(outcome),
+            _ => None,
+        };
+        exotic.winning_outcomes[round as usize] = winning_outcome;
+        exotic.settled_rounds += 1;
+
+        msg!(
+            "{:?} round {} settled: {:?}",
+            exotic.kind,
+            round,
+            winning_outcome
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleExoticMarket<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        mut,
+        seeds = [b"exotic", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref(), &[exotic_market.kind as u8]],
+        bump = exotic_market.bump,
+    )]
+    pub exotic_market: Account<'info, ExoticMarket>,
+}
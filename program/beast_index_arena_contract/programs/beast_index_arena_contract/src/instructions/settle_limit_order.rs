@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, OrderBook, UserPosition};
+use crate::errors::GameError;
+use crate::funds::close_limit_order;
+
+/// Takes a fully filled order, or any order once the battle is over, off the
+/// book and delivers it to its owner. Permissionless, so a crank can keep the
+/// book from filling up with finished orders.
+pub fn settle_limit_order(ctx: Context<SettleLimitOrder>, order_id: u64) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let book = &mut ctx.accounts.order_book;
+    let user = &ctx.accounts.user;
+
+    let index = book
+        .orders
+        .iter()
+        .position(|order| order.id == order_id && order.owner == user.key())
+        .ok_or(GameError::NoPendingOrders)?;
+    require!(
+        book.orders[index].remaining == 0 || battle.is_battle_over,
+        GameError::OrderStillOpen
+    );
+
+    let (lamports_returned, shares_returned) = close_limit_order(
+        book,
+        index,
+        &mut ctx.accounts.user_position,
+        &user.to_account_info(),
+    )?;
+
+    msg!(
+        "Settled limit order #{} for {}: {} lamports and {} shares",
+        order_id,
+        user.key(),
+        lamports_returned,
+        shares_returned
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleLimitOrder<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[user_position.creature_index]
+        ],
+        bump = user_position.bump,
+        has_one = user,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// CHECK: The order's owner, matched by `has_one` and the order itself;
+    /// only receives lamports.
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+}
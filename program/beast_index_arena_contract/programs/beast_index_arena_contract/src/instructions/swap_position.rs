@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, MarketState, OrderBook, ProtocolConfig, UserPosition};
+use crate::errors::GameError;
+use crate::funds::crank_after_trade;
+use crate::events::PositionSwapped;
+use crate::utils::{
+    calculate_buy_shares, calculate_sell_price, check_buy_limits, check_price_impact, get_lock_reason,
+};
+
+/// Sells shares of one creature and buys another with the proceeds in one
+/// step. The proceeds never leave the market, so it works the same for SOL
+/// and token markets.
+pub fn swap_position(
+    ctx: Context<SwapPosition>,
+    from_creature: u8,
+    to_creature: u8,
+    shares: u64,
+    min_out: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market_state;
+    let battle = &ctx.accounts.battle_state;
+    let from = &mut ctx.accounts.from_position;
+    let to = &mut ctx.accounts.to_position;
+
+    require!(to_creature < 4, GameError::InvalidCreatureIndex);
+    require!(from_creature != to_creature, GameError::SameCreature);
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    require!(!market.tokenized_shares, GameError::SharesAreTokenized);
+    require!(!market.config.batch_mode, GameError::BatchModeActive);
+    require!(
+        battle.is_alive[to_creature as usize],
+        GameError::CreatureIsDead
+    );
+    require!(!from.claimed, GameError::AlreadyClaimed);
+    require!(shares <= from.amount, GameError::InsufficientShares);
+
+    let clock = Clock::get()?;
+    if let Some(reason) = get_lock_reason(&market.config, battle, clock.unix_timestamp) {
+        msg!("Bets are locked: {:?}", reason);
+        return Err(GameError::BetsLocked.into());
+    }
+
+    let from_shares = market.creature_shares(from_creature)?;
+    check_price_impact(&market.config, from_shares, shares)?;
+    let amount = calculate_sell_price(from_shares, shares, market.k_constant)?;
+    ctx.accounts.protocol_config.check_bet(amount)?;
+    market.record_sell(from_creature, amount, shares)?;
+
+    let to_shares = market.creature_shares(to_creature)?;
+    let (net_amount, _) = market.split_trade_fee(amount)?;
+    let shares_bought = calculate_buy_shares(to_shares, net_amount, market.k_constant)?;
+    require!(shares_bought >= min_out, GameError::SlippageExceeded);
+    check_buy_limits(
+        market,
+        amount,
+        to_shares,
+        shares_bought,
+        to.amount.saturating_add(shares_bought),
+    )?;
+    market.record_buy(to_creature, net_amount, shares_bought)?;
+
+    let cost = from.remove_shares(shares)?;
+    market.remove_cost_basis(from_creature, cost)?;
+    market.add_cost_basis(to_creature, net_amount)?;
+    if from.amount == 0 {
+        from.claimed = true;
+    }
+
+    if to.user == Pubkey::default() {
+        to.user = ctx.accounts.user.key();
+        to.battle_id = battle.battle_id;
+        to.arena = battle.arena;
+        to.creature_index = to_creature;
+        to.amount = 0;
+        to.bump = ctx.bumps.to_position;
+        to.cost_basis = 0;
+    }
+    to.claimed = false;
+    // The new shares cost what the old ones sold for, not what they were bought at.
+    to.add_shares(shares_bought, net_amount)?;
+
+    emit!(PositionSwapped {
+        user: ctx.accounts.user.key(),
+        battle_id: battle.battle_id,
+        from_creature,
+        to_creature,
+        shares_sold: shares,
+        amount,
+        shares_bought,
+    });
+
+    crank_after_trade(market, ctx.accounts.order_book.as_mut(), battle, clock.unix_timestamp)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(from_creature: u8, to_creature: u8)]
+pub struct SwapPosition<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    /// Resting limit orders crossed by this trade fill right after it.
+    /// Required once the battle has a book.
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Option<Account<'info, OrderBook>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[from_creature]
+        ],
+        bump = from_position.bump,
+        has_one = user,
+    )]
+    pub from_position: Account<'info, UserPosition>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::LEN,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[to_creature]
+        ],
+        bump
+    )]
+    pub to_position: Account<'info, UserPosition>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
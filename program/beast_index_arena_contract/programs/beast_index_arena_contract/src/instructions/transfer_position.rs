@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, MarketState, UserPosition};
+use crate::errors::GameError;
+
+/// Moves shares from the sender's position to the recipient's position on the
+/// same creature, so bets can be gifted or sold over the counter. The
+/// recipient is held to the market's position limit like any buyer.
+pub fn transfer_position(
+    ctx: Context<TransferPosition>,
+    shares: u64,
+    recipient: Pubkey,
+) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let config = &ctx.accounts.market_state.config;
+    let from = &mut ctx.accounts.user_position;
+    let to = &mut ctx.accounts.recipient_position;
+
+    require!(shares > 0, GameError::ZeroAmount);
+    require_keys_neq!(recipient, ctx.accounts.user.key(), GameError::SelfTransfer);
+    require!(!battle.is_battle_over, GameError::BattleAlreadyOver);
+    require!(!battle.is_paused, GameError::BattlePaused);
+    require!(!from.claimed, GameError::AlreadyClaimed);
+    require!(shares <= from.amount, GameError::InsufficientShares);
+
+    if to.user == Pubkey::default() {
+        to.user = recipient;
+        to.battle_id = battle.battle_id;
+        to.arena = battle.arena;
+        to.creature_index = from.creature_index;
+        to.amount = 0;
+        to.bump = ctx.bumps.recipient_position;
+        to.cost_basis = 0;
+    }
+    // The cost basis goes with the shares, so a refund pays the recipient
+    // what the sender put in for them.
+    let cost = from.remove_shares(shares)?;
+    // A position sold down to zero is marked claimed; receiving shares reopens it.
+    to.claimed = false;
+    to.add_shares(shares, cost)?;
+    require!(
+        config.max_position == 0 || to.amount <= config.max_position,
+        GameError::PositionLimitExceeded
+    );
+
+    if from.amount == 0 {
+        from.claimed = true;
+    }
+
+    msg!(
+        "{} transferred {} shares of Creature {} to {}",
+        ctx.accounts.user.key(),
+        shares,
+        from.creature_index,
+        recipient
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(shares: u64, recipient: Pubkey)]
+pub struct TransferPosition<'info> {
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// Only read for its position limit.
+    #[account(
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+            &[user_position.creature_index]
+        ],
+        bump = user_position.bump,
+        has_one = user,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::LEN,
+        seeds = [
+            b"position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            recipient.as_ref(),
+            &[user_position.creature_index]
+        ],
+        bump
+    )]
+    pub recipient_position: Account<'info, UserPosition>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
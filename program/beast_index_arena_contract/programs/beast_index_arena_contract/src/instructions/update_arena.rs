@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::state::{Arena, BattleTemplate, GlobalState, Role};
+use crate::errors::GameError;
+
+/// Points an arena at another template and changes its fee. Battles and
+/// markets that already exist keep theirs.
+pub fn update_arena(ctx: Context<UpdateArena>, template_id: u64, trade_fee_bps: u16) -> Result<()> {
+    Arena::validate_trade_fee(trade_fee_bps)?;
+
+    let arena = &mut ctx.accounts.arena;
+    arena.template_id = template_id;
+    arena.trade_fee_bps = trade_fee_bps;
+
+    msg!("Arena {} updated", arena.name);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(template_id: u64)]
+pub struct UpdateArena<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::Admin) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// Only checked to exist.
+    #[account(
+        seeds = [b"template", template_id.to_le_bytes().as_ref()],
+        bump = template.bump,
+    )]
+    pub template: Account<'info, BattleTemplate>,
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.name.as_bytes()],
+        bump = arena.bump,
+    )]
+    pub arena: Account<'info, Arena>,
+
+    pub authority: Signer<'info>,
+}
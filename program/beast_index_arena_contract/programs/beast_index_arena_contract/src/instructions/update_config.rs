@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalState, ProtocolConfig, ProtocolParams};
+use crate::errors::GameError;
+use crate::events::ProtocolConfigUpdated;
+
+/// Replaces the protocol parameters. Markets keep the config they were
+/// created with; everything else picks the change up on its next instruction.
+pub fn update_config(ctx: Context<UpdateConfig>, params: ProtocolParams) -> Result<()> {
+    params.validate()?;
+
+    let config = &mut ctx.accounts.protocol_config;
+    let old_params = config.params;
+    config.params = params;
+    config.version = config.version.checked_add(1).ok_or(GameError::CalculationOverflow)?;
+
+    emit!(ProtocolConfigUpdated {
+        authority: ctx.accounts.authority.key(),
+        version: config.version,
+        old_params,
+        new_params: params,
+    });
+
+    msg!("Protocol config updated to version {}", config.version);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        has_one = authority @ GameError::Unauthorized,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleTemplate, GlobalState, Role, TemplateParams};
+use crate::errors::GameError;
+
+/// Replaces a template's params and bumps its version. Battles already created
+/// from it keep what they copied.
+pub fn update_template(ctx: Context<UpdateTemplate>, params: TemplateParams) -> Result<()> {
+    params.validate()?;
+
+    let template = &mut ctx.accounts.template;
+    template.params = params;
+    template.version = template.version.checked_add(1).ok_or(GameError::CalculationOverflow)?;
+
+    msg!(
+        "Battle template {} updated to version {}",
+        template.template_id,
+        template.version
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateTemplate<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = global_state.has_role(&authority.key(), Role::Admin) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"template", template.template_id.to_le_bytes().as_ref()],
+        bump = template.bump,
+    )]
+    pub template: Account<'info, BattleTemplate>,
+
+    pub authority: Signer<'info>,
+}
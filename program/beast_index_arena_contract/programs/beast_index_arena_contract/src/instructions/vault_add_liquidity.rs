@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, HouseVault, LpPosition, MarketState, OrderBook, Role};
+use crate::errors::GameError;
+use crate::funds::{available_funds, crank_after_trade};
+use crate::utils::{add_market_liquidity, get_lp_value};
+
+/// Deploys idle vault lamports into a market as LP liquidity owned by the
+/// vault. Treasury managers only.
+pub fn vault_add_liquidity(ctx: Context<VaultAddLiquidity>, amount: u64) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let market = &mut ctx.accounts.market_state;
+    let vault = &mut ctx.accounts.house_vault;
+    let lp_position = &mut ctx.accounts.vault_lp_position;
+
+    require!(amount > 0, GameError::BetTooSmall);
+    require!(
+        market.token_mint == Pubkey::default(),
+        GameError::UnsupportedForTokenMarket
+    );
+    require!(
+        !battle.is_battle_over && battle.current_turn == 0,
+        GameError::LiquidityWindowClosed
+    );
+
+    require!(vault.total_assets >= amount, GameError::InsufficientVaultLiquidity);
+
+    let lp_value = get_lp_value(market, available_funds(market, None)?);
+    **vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **market.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    let lp_shares = add_market_liquidity(market, amount, lp_value)?;
+
+    if lp_position.owner == Pubkey::default() {
+        lp_position.owner = vault.key();
+        lp_position.battle_id = battle.battle_id;
+        lp_position.arena = battle.arena;
+        lp_position.bump = ctx.bumps.vault_lp_position;
+    }
+    lp_position.shares = lp_position
+        .shares
+        .checked_add(lp_shares)
+        .ok_or(GameError::CalculationOverflow)?;
+    lp_position.deposited = lp_position
+        .deposited
+        .checked_add(amount)
+        .ok_or(GameError::CalculationOverflow)?;
+    vault.deployed = vault
+        .deployed
+        .checked_add(amount)
+        .ok_or(GameError::CalculationOverflow)?;
+    vault.total_assets -= amount;
+
+    msg!(
+        "House vault deployed {} lamports into battle {} for {} LP shares",
+        amount,
+        battle.battle_id,
+        lp_shares
+    );
+
+    crank_after_trade(market, ctx.accounts.order_book.as_mut(), battle, Clock::get()?.unix_timestamp)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VaultAddLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [b"house_vault"],
+        bump = house_vault.bump,
+    )]
+    pub house_vault: Account<'info, HouseVault>,
+
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.trading_paused @ GameError::TradingPaused,
+        constraint = global_state.has_role(&authority.key(), Role::TreasuryManager) @ GameError::MissingRole,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    /// Deepening the curves moves prices, so crossed limit orders fill right
+    /// after. Required once the battle has a book.
+    #[account(
+        mut,
+        seeds = [b"order_book", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Option<Account<'info, OrderBook>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = LpPosition::LEN,
+        seeds = [
+            b"lp_position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            house_vault.key().as_ref()
+        ],
+        bump
+    )]
+    pub vault_lp_position: Account<'info, LpPosition>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use crate::state::{BattleState, GlobalState, HouseVault, LpPosition, MarketState};
+use crate::errors::GameError;
+use crate::funds::available_funds;
+use crate::utils::get_lp_redemption;
+
+/// Pulls the vault's LP stake out of a finished market. Anyone can crank it;
+/// the lamports always go back to the vault, which books the profit or loss
+/// against every vault share. The stake doesn't expire with the claim window,
+/// and the market can't be closed until it's out.
+pub fn vault_lp_withdraw(ctx: Context<VaultLpWithdraw>) -> Result<()> {
+    let battle = &ctx.accounts.battle_state;
+    let market = &mut ctx.accounts.market_state;
+    let vault = &mut ctx.accounts.house_vault;
+    let lp_position = &mut ctx.accounts.vault_lp_position;
+
+    require!(battle.is_battle_over, GameError::BattleNotOver);
+    require!(lp_position.shares > 0, GameError::NoLpShares);
+
+    let payout = get_lp_redemption(
+        market,
+        battle,
+        available_funds(market, None)?,
+        lp_position.shares,
+    )?;
+
+    **market.to_account_info().try_borrow_mut_lamports()? -= payout;
+    **vault.to_account_info().try_borrow_mut_lamports()? += payout;
+
+    market.total_lp_shares -= lp_position.shares;
+    vault.deployed = vault.deployed.saturating_sub(lp_position.deposited);
+    vault.total_assets = vault
+        .total_assets
+        .checked_add(payout)
+        .ok_or(GameError::CalculationOverflow)?;
+
+    msg!(
+        "House vault recovered {} lamports from battle {} (deployed {})",
+        payout,
+        battle.battle_id,
+        lp_position.deposited
+    );
+
+    lp_position.shares = 0;
+    lp_position.deposited = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VaultLpWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"house_vault"],
+        bump = house_vault.bump,
+    )]
+    pub house_vault: Account<'info, HouseVault>,
+
+    #[account(
+        seeds = [b"battle", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = battle_state.bump,
+    )]
+    pub battle_state: Account<'info, BattleState>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"market", battle_state.arena.as_ref(), battle_state.battle_id.to_le_bytes().as_ref()],
+        bump = market_state.bump,
+    )]
+    pub market_state: Account<'info, MarketState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"lp_position",
+            battle_state.arena.as_ref(),
+            battle_state.battle_id.to_le_bytes().as_ref(),
+            house_vault.key().as_ref()
+        ],
+        bump = vault_lp_position.bump,
+    )]
+    pub vault_lp_position: Account<'info, LpPosition>,
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalState, HouseVault, VaultPosition};
+use crate::errors::GameError;
+
+pub fn withdraw_from_vault(ctx: Context<WithdrawFromVault>, shares: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.house_vault;
+    let position = &mut ctx.accounts.vault_position;
+
+    require!(
+        shares > 0 && shares <= position.shares,
+        GameError::InsufficientShares
+    );
+    // Same as deposits: shares are only priced once every market has paid
+    // the vault back.
+    require!(vault.deployed == 0, GameError::VaultCapitalDeployed);
+
+    let payout = (shares as u128)
+        .checked_mul(vault.total_assets as u128)
+        .ok_or(GameError::CalculationOverflow)?
+        .checked_div(vault.total_shares as u128)
+        .ok_or(GameError::DivisionByZero)? as u64;
+
+    **vault.to_account_info().try_borrow_mut_lamports()? -= payout;
+    **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? += payout;
+
+    position.shares -= shares;
+    vault.total_shares -= shares;
+    vault.total_assets -= payout;
+
+    msg!(
+        "{} redeemed {} vault shares for {} lamports",
+        ctx.accounts.owner.key(),
+        shares,
+        payout
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromVault<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global_state.bump,
+        constraint = !global_state.claims_paused @ GameError::ClaimsPaused,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"house_vault"],
+        bump = house_vault.bump,
+    )]
+    pub house_vault: Account<'info, HouseVault>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_position", owner.key().as_ref()],
+        bump = vault_position.bump,
+        has_one = owner,
+    )]
+    pub vault_position: Account<'info, VaultPosition>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
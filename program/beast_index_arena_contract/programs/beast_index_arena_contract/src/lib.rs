@@ -6,8 +6,13 @@ pub mod state;
 pub mod instructions;
 pub mod errors;
 pub mod utils;
+pub mod funds;
+pub mod events;
 
 use instructions::*;
+use state::{
+    ExoticKind, MarketConfig, MarketLockStatus, OverUnderLine, ProtocolParams, Role, TemplateParams,
+};
 
 #[program]
 pub mod beast_index_arena_contract {
@@ -17,38 +22,35 @@ pub mod beast_index_arena_contract {
         instructions::initialize_global::initialize_global(ctx)
     }
 
-    pub fn update_current_battle(ctx: Context<UpdateCurrentBattle>, battle_id: u64) -> Result<()> {
-        instructions::update_current_battle::update_current_battle(ctx, battle_id)
-    }
-
     pub fn initialize_battle(
         ctx: Context<InitializeBattle>,
-        battle_id: u64,
-        hp: u16,
-        atk: u16,
-        def: u16,
-        spd: u16,
-        turn_interval: i64,
-        max_duration: i64,
+        template_id: u64,
+        start_time: Option<i64>,
     ) -> Result<()> {
-        instructions::initialize_battle::initialize_battle(
-            ctx,
-            battle_id,
-            hp,
-            atk,
-            def,
-            spd,
-            turn_interval,
-            max_duration,
-        )
+        instructions::initialize_battle::initialize_battle(ctx, template_id, start_time)
     }
 
     pub fn initialize_market(
         ctx: Context<InitializeMarket>,
         battle_id: u64,
         initial_liquidity: u64,
+        config: Option<MarketConfig>,
     ) -> Result<()> {
-        instructions::initialize_market::initialize_market(ctx, battle_id, initial_liquidity)
+        instructions::initialize_market::initialize_market(ctx, battle_id, initial_liquidity, config)
+    }
+
+    pub fn initialize_token_market(
+        ctx: Context<InitializeTokenMarket>,
+        battle_id: u64,
+        initial_liquidity: u64,
+        config: Option<MarketConfig>,
+    ) -> Result<()> {
+        instructions::initialize_token_market::initialize_token_market(
+            ctx,
+            battle_id,
+            initial_liquidity,
+            config,
+        )
     }
 
     pub fn execute_turn(ctx: Context<ExecuteTurn>) -> Result<()> {
@@ -70,4 +72,258 @@ pub mod beast_index_arena_contract {
     pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
         instructions::claim_winnings::claim_winnings(ctx)
     }
+
+    pub fn pause_battle(ctx: Context<PauseBattle>) -> Result<()> {
+        instructions::pause_battle::pause_battle(ctx)
+    }
+
+    pub fn resume_battle(ctx: Context<ResumeBattle>) -> Result<()> {
+        instructions::resume_battle::resume_battle(ctx)
+    }
+
+    pub fn cancel_battle(ctx: Context<CancelBattle>) -> Result<()> {
+        instructions::cancel_battle::cancel_battle(ctx)
+    }
+
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        instructions::claim_refund::claim_refund(ctx)
+    }
+
+    pub fn get_lock_status(ctx: Context<GetLockStatus>) -> Result<MarketLockStatus> {
+        instructions::get_lock_status::get_lock_status(ctx)
+    }
+
+    pub fn queue_bet(ctx: Context<QueueBet>, creature_index: u8, amount: u64) -> Result<()> {
+        instructions::queue_bet::queue_bet(ctx, creature_index, amount)
+    }
+
+    pub fn queue_sell(ctx: Context<QueueSell>, shares_to_sell: u64) -> Result<()> {
+        instructions::queue_sell::queue_sell(ctx, shares_to_sell)
+    }
+
+    pub fn cancel_batch_orders(ctx: Context<CancelBatchOrders>) -> Result<()> {
+        instructions::cancel_batch_orders::cancel_batch_orders(ctx)
+    }
+
+    pub fn settle_batch_orders(ctx: Context<SettleBatchOrders>) -> Result<()> {
+        instructions::settle_batch_orders::settle_batch_orders(ctx)
+    }
+
+    pub fn lp_withdraw(ctx: Context<LpWithdraw>) -> Result<()> {
+        instructions::lp_withdraw::lp_withdraw(ctx)
+    }
+
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, amount: u64) -> Result<()> {
+        instructions::add_liquidity::add_liquidity(ctx, amount)
+    }
+
+    pub fn initialize_house_vault(ctx: Context<InitializeHouseVault>) -> Result<()> {
+        instructions::initialize_house_vault::initialize_house_vault(ctx)
+    }
+
+    pub fn deposit_to_vault(ctx: Context<DepositToVault>, amount: u64, min_shares: u64) -> Result<()> {
+        instructions::deposit_to_vault::deposit_to_vault(ctx, amount, min_shares)
+    }
+
+    pub fn withdraw_from_vault(ctx: Context<WithdrawFromVault>, shares: u64) -> Result<()> {
+        instructions::withdraw_from_vault::withdraw_from_vault(ctx, shares)
+    }
+
+    pub fn vault_add_liquidity(ctx: Context<VaultAddLiquidity>, amount: u64) -> Result<()> {
+        instructions::vault_add_liquidity::vault_add_liquidity(ctx, amount)
+    }
+
+    pub fn vault_lp_withdraw(ctx: Context<VaultLpWithdraw>) -> Result<()> {
+        instructions::vault_lp_withdraw::vault_lp_withdraw(ctx)
+    }
+
+    pub fn initialize_outcome_mints(ctx: Context<InitializeOutcomeMints>, decimals: u8) -> Result<()> {
+        instructions::initialize_outcome_mints::initialize_outcome_mints(ctx, decimals)
+    }
+
+    pub fn buy_outcome_tokens(ctx: Context<BuyOutcomeTokens>, creature_index: u8, amount: u64) -> Result<()> {
+        instructions::buy_outcome_tokens::buy_outcome_tokens(ctx, creature_index, amount)
+    }
+
+    pub fn sell_outcome_tokens(ctx: Context<SellOutcomeTokens>, creature_index: u8, shares_to_sell: u64) -> Result<()> {
+        instructions::sell_outcome_tokens::sell_outcome_tokens(ctx, creature_index, shares_to_sell)
+    }
+
+    pub fn redeem_outcome_tokens(ctx: Context<RedeemOutcomeTokens>, creature_index: u8) -> Result<()> {
+        instructions::redeem_outcome_tokens::redeem_outcome_tokens(ctx, creature_index)
+    }
+
+    pub fn transfer_position(ctx: Context<TransferPosition>, shares: u64, recipient: Pubkey) -> Result<()> {
+        instructions::transfer_position::transfer_position(ctx, shares, recipient)
+    }
+
+    pub fn swap_position(
+        ctx: Context<SwapPosition>,
+        from_creature: u8,
+        to_creature: u8,
+        shares: u64,
+        min_out: u64,
+    ) -> Result<()> {
+        instructions::swap_position::swap_position(ctx, from_creature, to_creature, shares, min_out)
+    }
+
+    pub fn place_basket_bet(ctx: Context<PlaceBasketBet>, weights: [u16; 4], amount: u64) -> Result<()> {
+        instructions::place_basket_bet::place_basket_bet(ctx, weights, amount)
+    }
+
+    pub fn place_field_bet(ctx: Context<PlaceBasketBet>, against: u8, amount: u64) -> Result<()> {
+        instructions::place_basket_bet::place_field_bet(ctx, against, amount)
+    }
+
+    pub fn initialize_exotic_market(ctx: Context<InitializeExoticMarket>, kind: ExoticKind) -> Result<()> {
+        instructions::initialize_exotic_market::initialize_exotic_market(ctx, kind)
+    }
+
+    pub fn place_exotic_bet(ctx: Context<PlaceExoticBet>, round: u8, selection: [u8; 3], amount: u64) -> Result<()> {
+        instructions::place_exotic_bet::place_exotic_bet(ctx, round, selection, amount)
+    }
+
+    pub fn settle_exotic_market(ctx: Context<SettleExoticMarket>) -> Result<()> {
+        instructions::settle_exotic_market::settle_exotic_market(ctx)
+    }
+
+    pub fn claim_exotic_winnings(ctx: Context<ClaimExoticWinnings>) -> Result<()> {
+        instructions::claim_exotic_winnings::claim_exotic_winnings(ctx)
+    }
+
+    pub fn initialize_over_under_market(ctx: Context<InitializeOverUnderMarket>, line: OverUnderLine) -> Result<()> {
+        instructions::initialize_over_under_market::initialize_over_under_market(ctx, line)
+    }
+
+    pub fn place_limit_order(
+        ctx: Context<PlaceLimitOrder>,
+        creature_index: u8,
+        is_buy: bool,
+        limit_price: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::place_limit_order::place_limit_order(ctx, creature_index, is_buy, limit_price, amount)
+    }
+
+    pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>, order_id: u64) -> Result<()> {
+        instructions::cancel_limit_order::cancel_limit_order(ctx, order_id)
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        instructions::close_position::close_position(ctx)
+    }
+
+    pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+        instructions::close_market::close_market(ctx)
+    }
+
+    pub fn close_battle(ctx: Context<CloseBattle>) -> Result<()> {
+        instructions::close_battle::close_battle(ctx)
+    }
+
+    pub fn set_treasury(ctx: Context<SetTreasury>, treasury: Pubkey) -> Result<()> {
+        instructions::set_treasury::set_treasury(ctx, treasury)
+    }
+
+    pub fn create_round(ctx: Context<CreateRound>, initial_liquidity: u64) -> Result<()> {
+        instructions::create_round::create_round(ctx, initial_liquidity)
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, member: Pubkey, role: Role) -> Result<()> {
+        instructions::grant_role::grant_role(ctx, member, role)
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, member: Pubkey, role: Role) -> Result<()> {
+        instructions::revoke_role::revoke_role(ctx, member, role)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_authority::propose_authority(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority::accept_authority(ctx)
+    }
+
+    pub fn cancel_authority_proposal(ctx: Context<CancelAuthorityProposal>) -> Result<()> {
+        instructions::cancel_authority_proposal::cancel_authority_proposal(ctx)
+    }
+
+    pub fn propose_battle_authority(ctx: Context<ProposeBattleAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_battle_authority::propose_battle_authority(ctx, new_authority)
+    }
+
+    pub fn accept_battle_authority(ctx: Context<AcceptBattleAuthority>) -> Result<()> {
+        instructions::accept_battle_authority::accept_battle_authority(ctx)
+    }
+
+    pub fn cancel_battle_authority_proposal(ctx: Context<CancelBattleAuthorityProposal>) -> Result<()> {
+        instructions::cancel_battle_authority_proposal::cancel_battle_authority_proposal(ctx)
+    }
+
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ProtocolParams) -> Result<()> {
+        instructions::initialize_config::initialize_config(ctx, params)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ProtocolParams) -> Result<()> {
+        instructions::update_config::update_config(ctx, params)
+    }
+
+    pub fn set_global_pause(
+        ctx: Context<SetGlobalPause>,
+        trading: bool,
+        turns: bool,
+        claims: bool,
+    ) -> Result<()> {
+        instructions::set_global_pause::set_global_pause(ctx, trading, turns, claims)
+    }
+
+    pub fn create_arena(
+        ctx: Context<CreateArena>,
+        name: String,
+        template_id: u64,
+        trade_fee_bps: Option<u16>,
+    ) -> Result<()> {
+        instructions::create_arena::create_arena(ctx, name, template_id, trade_fee_bps)
+    }
+
+    pub fn update_arena(ctx: Context<UpdateArena>, template_id: u64, trade_fee_bps: u16) -> Result<()> {
+        instructions::update_arena::update_arena(ctx, template_id, trade_fee_bps)
+    }
+
+    pub fn create_template(ctx: Context<CreateTemplate>, params: TemplateParams) -> Result<()> {
+        instructions::create_template::create_template(ctx, params)
+    }
+
+    pub fn update_template(ctx: Context<UpdateTemplate>, params: TemplateParams) -> Result<()> {
+        instructions::update_template::update_template(ctx, params)
+    }
+
+    pub fn settle_limit_order(ctx: Context<SettleLimitOrder>, order_id: u64) -> Result<()> {
+        instructions::settle_limit_order::settle_limit_order(ctx, order_id)
+    }
+
+    pub fn close_order_queue(ctx: Context<CloseOrderQueue>) -> Result<()> {
+        instructions::close_order_queue::close_order_queue(ctx)
+    }
+
+    pub fn close_order_book(ctx: Context<CloseOrderBook>) -> Result<()> {
+        instructions::close_order_book::close_order_book(ctx)
+    }
+
+    pub fn close_lp_position(ctx: Context<CloseLpPosition>) -> Result<()> {
+        instructions::close_lp_position::close_lp_position(ctx)
+    }
+
+    pub fn close_vault_position(ctx: Context<CloseVaultPosition>) -> Result<()> {
+        instructions::close_vault_position::close_vault_position(ctx)
+    }
+
+    pub fn close_exotic_market(ctx: Context<CloseExoticMarket>) -> Result<()> {
+        instructions::close_exotic_market::close_exotic_market(ctx)
+    }
+
+    pub fn close_exotic_ticket(ctx: Context<CloseExoticTicket>) -> Result<()> {
+        instructions::close_exotic_ticket::close_exotic_ticket(ctx)
+    }
 }
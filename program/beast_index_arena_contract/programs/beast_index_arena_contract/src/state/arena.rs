@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::errors::GameError;
+use crate::state::ProtocolParams;
+
+/// A named lane of battles with its own id counter, rule preset and fee, so
+/// several featured battles can run side by side.
+#[account]
+pub struct Arena {
+    pub name: String,
+    /// Newest battle created in this arena. It keeps pointing there once that
+    /// battle ends or is cancelled, until the next one is created, so check
+    /// the battle's `is_battle_over` before treating it as live.
+    pub current_battle_id: u64,
+    /// Id the arena's next battle will get.
+    pub next_battle_id: u64,
+    /// Battle template `create_round` builds this arena's rounds from.
+    pub template_id: u64,
+    /// Trade fee copied into each new market, in basis points.
+    pub trade_fee_bps: u16,
+    pub bump: u8,
+}
+
+impl Arena {
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const LEN: usize = 8 + (4 + Self::MAX_NAME_LEN) + 8 + 8 + 8 + 2 + 1 + 100;
+
+    pub fn validate_trade_fee(trade_fee_bps: u16) -> Result<()> {
+        require!(
+            trade_fee_bps <= ProtocolParams::MAX_TRADE_FEE_BPS,
+            GameError::InvalidArenaConfig
+        );
+        Ok(())
+    }
+
+    /// Points `current_battle_id` at a battle that was just created from the
+    /// counter, and advances the counter.
+    pub fn start_battle(&mut self, battle_id: u64) -> Result<()> {
+        self.current_battle_id = battle_id;
+        self.next_battle_id = battle_id.checked_add(1).ok_or(GameError::CalculationOverflow)?;
+        Ok(())
+    }
+}
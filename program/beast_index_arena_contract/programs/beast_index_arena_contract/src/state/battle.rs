@@ -1,10 +1,17 @@
 use anchor_lang::prelude::*;
+use crate::state::{GlobalState, Tiebreak};
 
 #[account]
+#[derive(Default)]
 pub struct BattleState {
     pub battle_id: u64,
+    pub arena: Pubkey,
     pub authority: Pubkey,
 
+    /// Template the battle was created from, and its version at the time.
+    pub template_id: u64,
+    pub template_version: u32,
+
     pub creature_hp: [u16; 4],
     pub creature_max_hp: [u16; 4],
     pub creature_atk: [u16; 4],
@@ -21,6 +28,39 @@ pub struct BattleState {
     pub turn_interval: i64,
     pub max_duration: i64,
 
+    /// Damage multiplier for each `Ability`, in basis points of the base damage.
+    pub ability_multipliers_bps: [u16; 3],
+    /// Decides the winner if the battle times out.
+    pub tiebreak: Tiebreak,
+
+    pub is_paused: bool,
+    pub paused_at: i64,
+    pub is_cancelled: bool,
+
+    /// Creatures in the order they died; only the first `death_count` entries are set.
+    pub death_order: [u8; 4],
+    pub death_count: u8,
+
+    /// When the battle finished, was cancelled or timed out; zero while running.
+    pub ended_at: i64,
+
+    /// Proposed new authority; takes over once it accepts. Default when none.
+    pub pending_authority: Pubkey,
+
+    /// Set once the market's order queue exists; every turn must then clear it.
+    pub has_order_queue: bool,
+    /// Set once the market's order book exists; every trade and turn must
+    /// then crank it.
+    pub has_order_book: bool,
+    /// Exotic markets opened on the battle and not yet closed.
+    pub open_exotic_markets: u8,
+
+    /// Global turns pause time already added to the turn clock.
+    pub turns_pause_applied: i64,
+    /// Global claims pause time when the battle ended; claims paused after
+    /// that extend the claim window.
+    pub claims_pause_at_end: i64,
+
     pub bump: u8,
 }
 
@@ -28,12 +68,15 @@ impl BattleState {
     pub const LEN: usize = 8
         + 8
         + 32
+        + 32
+        + 8
+        + 4
         + (2 * 4)
         + (2 * 4)
         + (2 * 4)
         + (2 * 4)
         + (2 * 4)
-        + (1 * 4)
+        + 4
         + 1
         + 2
         + 8
@@ -41,8 +84,67 @@ impl BattleState {
         + 8
         + 8
         + 8
+        + (2 * 3)
+        + 1
+        + 1
+        + 8
+        + 1
+        + 4
+        + 1
+        + 8
+        + 32
+        + 1
+        + 1
+        + 1
+        + 8
+        + 8
         + 1
         + 100;
+
+    pub fn ability_multiplier_bps(&self, ability: Ability) -> u16 {
+        match ability {
+            Ability::BasicHit => self.ability_multipliers_bps[0],
+            Ability::HeavyStrike => self.ability_multipliers_bps[1],
+            Ability::QuickJab => self.ability_multipliers_bps[2],
+        }
+    }
+
+    /// Shifts the turn clock by any protocol-wide turns pause since the last
+    /// turn, the same way `resume_battle` does for this battle's own pauses.
+    pub fn catch_up_turns_pause(&mut self, global: &GlobalState, now: i64) {
+        let paused_total = global.turns_paused_total(now);
+        let paused_for = paused_total - self.turns_pause_applied;
+        self.start_time += paused_for;
+        self.last_turn_time += paused_for;
+        self.turns_pause_applied = paused_total;
+    }
+
+    /// Last moment anything from this battle can be claimed: `window_secs`
+    /// after it ended, plus however long claims were paused since.
+    pub fn claim_deadline(&self, window_secs: i64, global: &GlobalState, now: i64) -> i64 {
+        let paused_since_end = global.claims_paused_total(now) - self.claims_pause_at_end;
+        self.ended_at
+            .saturating_add(window_secs)
+            .saturating_add(paused_since_end)
+    }
+
+    /// Winner of a battle that ran out its `max_duration`, under its tiebreak.
+    pub fn timeout_winner(&self) -> Option<u8> {
+        match self.tiebreak {
+            Tiebreak::Draw => None,
+            Tiebreak::HighestHp => {
+                let best = (0..4)
+                    .filter(|&i| self.is_alive[i])
+                    .map(|i| self.creature_hp[i])
+                    .max()?;
+                let mut leaders = (0..4).filter(|&i| self.is_alive[i] && self.creature_hp[i] == best);
+                match (leaders.next(), leaders.next()) {
+                    (Some(i), None) => Some(i as u8),
+                    _ => None,
+                }
+            }
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
@@ -78,8 +180,10 @@ pub struct Attack {
 
 impl Attack {
     pub const LEN: usize = 1 + 1 + 1 + 2 + 2 + 1;
+}
 
-    pub fn default() -> Self {
+impl Default for Attack {
+    fn default() -> Self {
         Self {
             attacker: 0,
             target: 0,
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use crate::errors::GameError;
+use crate::state::Ability;
+
+/// Protocol-wide parameters, set with `update_config`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ProtocolParams {
+    /// Smallest bet, in the market's currency.
+    pub min_bet: u64,
+    /// Largest bet on any market, on top of each market's own `max_bet`. Zero means no cap.
+    pub max_bet: u64,
+    /// Trade fee for arenas created without one, in basis points.
+    pub trade_fee_bps: u16,
+    /// Used when a battle's template has a zero turn interval.
+    pub default_turn_interval: i64,
+    /// Used when a battle's template has a zero max duration.
+    pub default_max_duration: i64,
+    /// Damage multiplier for each `Ability`, in basis points of the base damage.
+    /// Used for any the battle's template leaves at zero.
+    pub ability_multipliers_bps: [u16; 3],
+    /// Share prices and limit prices are quoted in lamports per this many
+    /// shares. Copied into each market when it's created.
+    pub price_scale: u64,
+    /// Claim window for markets whose config leaves it at zero, and for
+    /// exotic markets. Copied into each market when it's created.
+    pub default_claim_window_secs: i64,
+}
+
+impl ProtocolParams {
+    pub const LEN: usize = 8 + 8 + 2 + 8 + 8 + (2 * 3) + 8 + 8;
+    pub const MAX_TRADE_FEE_BPS: u16 = 1_000;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_bet > 0
+                && (self.max_bet == 0 || self.max_bet >= self.min_bet)
+                && self.trade_fee_bps <= Self::MAX_TRADE_FEE_BPS
+                && self.default_turn_interval > 0
+                && self.default_max_duration > 0
+                && self.ability_multipliers_bps.iter().all(|&m| m > 0)
+                && self.price_scale > 0
+                && self.default_claim_window_secs > 0,
+            GameError::InvalidProtocolConfig
+        );
+        Ok(())
+    }
+
+    pub fn ability_multiplier_bps(&self, ability: Ability) -> u16 {
+        match ability {
+            Ability::BasicHit => self.ability_multipliers_bps[0],
+            Ability::HeavyStrike => self.ability_multipliers_bps[1],
+            Ability::QuickJab => self.ability_multipliers_bps[2],
+        }
+    }
+}
+
+impl Default for ProtocolParams {
+    fn default() -> Self {
+        Self {
+            min_bet: 10_000_000,
+            max_bet: 0,
+            trade_fee_bps: 0,
+            default_turn_interval: 10,
+            default_max_duration: 24 * 60 * 60,
+            ability_multipliers_bps: [10_000, 15_000, 7_500],
+            price_scale: 1_000_000_000,
+            default_claim_window_secs: 30 * 24 * 60 * 60,
+        }
+    }
+}
+
+#[account]
+pub struct ProtocolConfig {
+    /// Bumped on every update.
+    pub version: u32,
+    pub params: ProtocolParams,
+    pub bump: u8,
+}
+
+impl ProtocolConfig {
+    pub const LEN: usize = 8 + 4 + ProtocolParams::LEN + 1 + 100;
+
+    pub fn check_bet(&self, amount: u64) -> Result<()> {
+        require!(amount >= self.params.min_bet, GameError::BetTooSmall);
+        require!(
+            self.params.max_bet == 0 || amount <= self.params.max_bet,
+            GameError::BetTooLarge
+        );
+        Ok(())
+    }
+}
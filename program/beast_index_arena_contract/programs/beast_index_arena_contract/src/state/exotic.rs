@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use crate::errors::GameError;
+
+/// Side markets on how a battle unfolds, settled parimutuel from `BattleState`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExoticKind {
+    /// Winner and runner-up, in order.
+    Exacta,
+    /// Winner, runner-up and third place, in order.
+    Trifecta,
+    /// First creature to die.
+    FirstBlood,
+    /// Rolling market: round `n` pays out on the `n`-th death, then round `n + 1` opens.
+    NextDeath,
+    /// Whether the battle runs under (outcome 0) or over (outcome 1) the market's line.
+    OverUnder,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineUnit {
+    #[default]
+    Turns,
+    Seconds,
+}
+
+/// Battle length an over/under market is set at. An exact hit is a push.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OverUnderLine {
+    pub value: u64,
+    pub unit: LineUnit,
+}
+
+impl OverUnderLine {
+    pub const LEN: usize = 8 + 1;
+}
+
+impl ExoticKind {
+    pub const MAX_POOLS: usize = 24;
+
+    /// Number of distinct outcomes per round.
+    pub fn outcomes(self) -> usize {
+        match self {
+            ExoticKind::Exacta => 12,
+            ExoticKind::Trifecta => 24,
+            ExoticKind::FirstBlood => 4,
+            ExoticKind::NextDeath => 4,
+            ExoticKind::OverUnder => 2,
+        }
+    }
+
+    /// Rounds the market runs for. Only the rolling market has more than one,
+    /// one per death that still leaves a fight to bet on.
+    pub fn rounds(self) -> usize {
+        match self {
+            ExoticKind::NextDeath => 3,
+            _ => 1,
+        }
+    }
+}
+
+#[account]
+pub struct ExoticMarket {
+    pub battle_id: u64,
+    pub kind: ExoticKind,
+    /// Stake per outcome, laid out round after round.
+    pub pools: [u64; ExoticKind::MAX_POOLS],
+    pub round_totals: [u64; 3],
+    /// Rounds below this one are settled.
+    pub settled_rounds: u8,
+    /// Winning outcome per settled round; `None` means the round refunds.
+    pub winning_outcomes: [Option<u8>; 3],
+    /// Over/under markets only.
+    pub line: OverUnderLine,
+    /// Copied from the protocol's `default_claim_window_secs` when the market
+    /// is opened.
+    pub claim_window_secs: i64,
+    pub bump: u8,
+}
+
+impl ExoticMarket {
+    pub const LEN: usize =
+        8 + 8 + 1 + (8 * ExoticKind::MAX_POOLS) + (8 * 3) + 1 + (2 * 3) + OverUnderLine::LEN + 8 + 1 + 50;
+
+    pub fn pool_index(&self, round: u8, outcome: u8) -> Result<usize> {
+        require!((round as usize) < self.kind.rounds(), GameError::ExoticBettingClosed);
+        require!((outcome as usize) < self.kind.outcomes(), GameError::InvalidSelection);
+        Ok(round as usize * self.kind.outcomes() + outcome as usize)
+    }
+}
+
+#[account]
+pub struct ExoticTicket {
+    pub user: Pubkey,
+    pub battle_id: u64,
+    pub arena: Pubkey,
+    pub kind: ExoticKind,
+    pub round: u8,
+    pub outcome: u8,
+    pub amount: u64,
+    pub claimed: bool,
+    pub bump: u8,
+}
+
+impl ExoticTicket {
+    pub const LEN: usize = 8 + 32 + 8 + 32 + 1 + 1 + 1 + 8 + 1 + 1 + 50;
+}
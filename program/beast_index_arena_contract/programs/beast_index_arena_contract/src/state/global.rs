@@ -1,12 +1,143 @@
 use anchor_lang::prelude::*;
+use crate::errors::GameError;
+
+/// Named permissions held by accounts other than the global authority, which
+/// implicitly holds every role.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// Grants and revokes roles.
+    Admin,
+    /// Creates battles and their markets.
+    BattleCreator,
+    /// Executes turns.
+    CrankOperator,
+    /// Sets the treasury, sets up the house vault and closes finished accounts.
+    TreasuryManager,
+    /// Pauses and resumes battles.
+    Pauser,
+}
+
+impl Role {
+    pub fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RoleMember {
+    pub member: Pubkey,
+    /// Bitmask of `Role::bit`s.
+    pub roles: u8,
+}
+
+impl RoleMember {
+    pub const LEN: usize = 32 + 1;
+}
 
 #[account]
+#[derive(Default)]
 pub struct GlobalState {
-    pub current_battle_id: u64,
     pub authority: Pubkey,
+    /// Receives lamports swept from closed markets and battles.
+    pub treasury: Pubkey,
+    /// Empty slots have a default `member`.
+    pub role_members: [RoleMember; GlobalState::MAX_ROLE_MEMBERS],
+    /// Proposed new authority; takes over once it accepts. Default when none.
+    pub pending_authority: Pubkey,
+    /// Emergency stops across every battle, set by pausers.
+    pub trading_paused: bool,
+    pub turns_paused: bool,
+    pub claims_paused: bool,
+    /// Id the next battle template will get.
+    pub next_template_id: u64,
+    /// When the current turns or claims pause started; zero while off.
+    pub turns_paused_at: i64,
+    pub claims_paused_at: i64,
+    /// Seconds turns and claims spent paused in earlier, finished pauses.
+    pub turns_paused_secs: i64,
+    pub claims_paused_secs: i64,
     pub bump: u8,
 }
 
 impl GlobalState {
-    pub const LEN: usize = 8 + 8 + 32 + 1 + 50;
+    pub const MAX_ROLE_MEMBERS: usize = 16;
+    pub const LEN: usize = 8 + 32 + 32 + RoleMember::LEN * Self::MAX_ROLE_MEMBERS + 32 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 50;
+
+    pub fn has_role(&self, key: &Pubkey, role: Role) -> bool {
+        *key == self.authority
+            || self
+                .role_members
+                .iter()
+                .any(|m| m.member == *key && m.roles & role.bit() != 0)
+    }
+
+    /// Sets the pause flags, keeping track of how long turns and claims have
+    /// been paused so battles and claim windows can make up for it.
+    pub fn set_pause(&mut self, trading: bool, turns: bool, claims: bool, now: i64) {
+        self.trading_paused = trading;
+        if turns != self.turns_paused {
+            if turns {
+                self.turns_paused_at = now;
+            } else {
+                self.turns_paused_secs += now - self.turns_paused_at;
+                self.turns_paused_at = 0;
+            }
+            self.turns_paused = turns;
+        }
+        if claims != self.claims_paused {
+            if claims {
+                self.claims_paused_at = now;
+            } else {
+                self.claims_paused_secs += now - self.claims_paused_at;
+                self.claims_paused_at = 0;
+            }
+            self.claims_paused = claims;
+        }
+    }
+
+    /// Seconds turns have been paused in total, including a pause still on.
+    pub fn turns_paused_total(&self, now: i64) -> i64 {
+        match self.turns_paused {
+            true => self.turns_paused_secs + (now - self.turns_paused_at),
+            false => self.turns_paused_secs,
+        }
+    }
+
+    /// Seconds claims have been paused in total, including a pause still on.
+    pub fn claims_paused_total(&self, now: i64) -> i64 {
+        match self.claims_paused {
+            true => self.claims_paused_secs + (now - self.claims_paused_at),
+            false => self.claims_paused_secs,
+        }
+    }
+
+    pub fn grant_role(&mut self, member: Pubkey, role: Role) -> Result<()> {
+        require!(member != Pubkey::default(), GameError::InvalidRoleMember);
+
+        let slot = match self.role_members.iter().position(|m| m.member == member) {
+            Some(i) => i,
+            None => self
+                .role_members
+                .iter()
+                .position(|m| m.member == Pubkey::default())
+                .ok_or(GameError::RoleListFull)?,
+        };
+        self.role_members[slot].member = member;
+        self.role_members[slot].roles |= role.bit();
+        Ok(())
+    }
+
+    /// Frees the member's slot once its last role is gone.
+    pub fn revoke_role(&mut self, member: Pubkey, role: Role) -> Result<()> {
+        let entry = self
+            .role_members
+            .iter_mut()
+            .find(|m| m.member == member && m.roles & role.bit() != 0)
+            .ok_or(GameError::RoleNotHeld)?;
+        entry.roles &= !role.bit();
+        if entry.roles == 0 {
+            *entry = RoleMember::default();
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+/// A liquidity provider's claim on one market's house side.
+#[account]
+pub struct LpPosition {
+    pub owner: Pubkey,
+    pub battle_id: u64,
+    pub arena: Pubkey,
+    pub shares: u64,
+    /// Lamports put in for these shares, used by the house vault to book profit or loss.
+    pub deposited: u64,
+    pub bump: u8,
+}
+
+impl LpPosition {
+    pub const LEN: usize = 8 + 32 + 8 + 32 + 8 + 8 + 1 + 50;
+}
+
+/// Shared pool that community LPs fund and treasury managers deploy into
+/// markets.
+#[account]
+pub struct HouseVault {
+    pub total_shares: u64,
+    /// Idle lamports the shares are priced against. Tracked rather than read
+    /// off the account, so lamports sent to it directly can't move the price.
+    pub total_assets: u64,
+    /// Lamports currently lent out to markets, at cost.
+    pub deployed: u64,
+    pub bump: u8,
+}
+
+impl HouseVault {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 1 + 50;
+}
+
+#[account]
+pub struct VaultPosition {
+    pub owner: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+impl VaultPosition {
+    pub const LEN: usize = 8 + 32 + 8 + 1 + 50;
+}
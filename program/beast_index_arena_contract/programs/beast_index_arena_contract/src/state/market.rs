@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
+use crate::errors::GameError;
+use crate::state::BattleState;
 
 #[account]
+#[derive(Default)]
 pub struct MarketState {
     pub battle_id: u64,
+    pub arena: Pubkey,
 
     pub creature_0_pool: u64,
     pub creature_1_pool: u64,
@@ -18,10 +22,176 @@ pub struct MarketState {
     pub creature_3_shares: u64,
 
     pub k_constant: u128,
+    pub initial_liquidity: u64,
+    pub refund_mode: bool,
+    pub config: MarketConfig,
+
+    pub lp_deposit: u64,
+    pub total_lp_shares: u64,
+    pub winning_shares_claimed: u64,
+    pub batch_fills_owed: u64,
+
+    /// SPL mint the market is priced in; the default key means SOL.
+    pub token_mint: Pubkey,
+    /// Shares are held as SPL outcome tokens instead of `UserPosition`s.
+    pub tokenized_shares: bool,
+
+    /// Cut of every direct buy that stays in the market for its LPs, in basis
+    /// points. Copied from the arena when the market is created.
+    pub trade_fee_bps: u16,
+
+    /// Cost basis bettors still hold per creature, in positions and orders.
+    /// Refunds split a creature's pool by it when the pool falls short.
+    pub cost_basis_outstanding: [u64; 4],
+
+    /// Shares `get_share_price` quotes a price for. Copied from the protocol
+    /// config when the market is created, so resting limit orders keep their
+    /// meaning.
+    pub price_scale: u64,
 
     pub bump: u8,
 }
 
 impl MarketState {
-    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 16 + 1 + 100;
+    pub const LEN: usize = 8 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 16 + 8 + 1 + MarketConfig::LEN
+        + 8 + 8 + 8 + 8
+        + 32 + 1
+        + 2
+        + (8 * 4)
+        + 8
+        + 1 + 100;
+
+    /// Splits a buy into what goes into the curve and the trade fee.
+    pub fn split_trade_fee(&self, amount: u64) -> Result<(u64, u64)> {
+        let fee = (amount as u128)
+            .checked_mul(self.trade_fee_bps as u128)
+            .ok_or(GameError::CalculationOverflow)?
+            / 10_000;
+        let fee = fee as u64;
+        Ok((amount - fee, fee))
+    }
+
+    /// Cancelled battles, draws and timeouts without a winner refund every
+    /// bettor instead of paying out a winning pool.
+    pub fn is_refunding(&self, battle: &BattleState) -> bool {
+        self.refund_mode || (battle.is_battle_over && battle.winner.is_none())
+    }
+
+    pub fn creature_pool(&self, creature_index: u8) -> Result<u64> {
+        match creature_index {
+            0 => Ok(self.creature_0_pool),
+            1 => Ok(self.creature_1_pool),
+            2 => Ok(self.creature_2_pool),
+            3 => Ok(self.creature_3_pool),
+            _ => Err(GameError::InvalidCreatureIndex.into()),
+        }
+    }
+
+    pub fn creature_shares(&self, creature_index: u8) -> Result<u64> {
+        match creature_index {
+            0 => Ok(self.creature_0_shares),
+            1 => Ok(self.creature_1_shares),
+            2 => Ok(self.creature_2_shares),
+            3 => Ok(self.creature_3_shares),
+            _ => Err(GameError::InvalidCreatureIndex.into()),
+        }
+    }
+
+    /// Books a buy: `amount` goes into the creature's pool and `shares` leave its curve.
+    pub fn record_buy(&mut self, creature_index: u8, amount: u64, shares: u64) -> Result<()> {
+        let (pool, curve_shares) = match creature_index {
+            0 => (&mut self.creature_0_pool, &mut self.creature_0_shares),
+            1 => (&mut self.creature_1_pool, &mut self.creature_1_shares),
+            2 => (&mut self.creature_2_pool, &mut self.creature_2_shares),
+            3 => (&mut self.creature_3_pool, &mut self.creature_3_shares),
+            _ => return Err(GameError::InvalidCreatureIndex.into()),
+        };
+        *pool = pool.checked_add(amount).ok_or(GameError::CalculationOverflow)?;
+        *curve_shares = curve_shares.checked_sub(shares).ok_or(GameError::CalculationOverflow)?;
+        self.total_pool = self.total_pool.checked_add(amount).ok_or(GameError::CalculationOverflow)?;
+        Ok(())
+    }
+
+    /// Books cost basis handed to bettors by a buy.
+    pub fn add_cost_basis(&mut self, creature_index: u8, cost: u64) -> Result<()> {
+        let outstanding = self
+            .cost_basis_outstanding
+            .get_mut(creature_index as usize)
+            .ok_or(GameError::InvalidCreatureIndex)?;
+        *outstanding = outstanding.checked_add(cost).ok_or(GameError::CalculationOverflow)?;
+        Ok(())
+    }
+
+    /// Books cost basis leaving bettors' hands through a sell or refund.
+    pub fn remove_cost_basis(&mut self, creature_index: u8, cost: u64) -> Result<()> {
+        let outstanding = self
+            .cost_basis_outstanding
+            .get_mut(creature_index as usize)
+            .ok_or(GameError::InvalidCreatureIndex)?;
+        *outstanding = outstanding.saturating_sub(cost);
+        Ok(())
+    }
+
+    /// Books a sell or refund: `amount` leaves the creature's pool and `shares` return to its curve.
+    pub fn record_sell(&mut self, creature_index: u8, amount: u64, shares: u64) -> Result<()> {
+        let (pool, curve_shares) = match creature_index {
+            0 => (&mut self.creature_0_pool, &mut self.creature_0_shares),
+            1 => (&mut self.creature_1_pool, &mut self.creature_1_shares),
+            2 => (&mut self.creature_2_pool, &mut self.creature_2_shares),
+            3 => (&mut self.creature_3_pool, &mut self.creature_3_shares),
+            _ => return Err(GameError::InvalidCreatureIndex.into()),
+        };
+        *pool = pool.checked_sub(amount).ok_or(GameError::CalculationOverflow)?;
+        *curve_shares = curve_shares.checked_add(shares).ok_or(GameError::CalculationOverflow)?;
+        self.total_pool = self.total_pool.checked_sub(amount).ok_or(GameError::CalculationOverflow)?;
+        Ok(())
+    }
+}
+
+/// Per-market trading rules. Zero disables a lock rule or limit.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct MarketConfig {
+    /// No new bets once this many turns have been executed.
+    pub lock_after_turn: u64,
+    /// No new bets once this many creatures or fewer are alive.
+    pub lock_at_alive_count: u8,
+    /// No new bets within this many seconds of `max_duration`.
+    pub lock_before_end_secs: i64,
+    /// Whether holders can still sell while bets are locked.
+    pub allow_sells_when_locked: bool,
+    /// Queue trades and clear them at one price per creature when the next turn starts.
+    pub batch_mode: bool,
+    /// Largest single bet, in the market's currency.
+    pub max_bet: u64,
+    /// Most shares one user may hold on one creature. Not enforced once shares
+    /// are tokenized, since outcome tokens can be moved to any wallet.
+    pub max_position: u64,
+    /// Cap on the market's total pool.
+    pub max_total_pool: u64,
+    /// Most of a creature's curve one trade may take, in basis points of its
+    /// remaining shares.
+    pub max_price_impact_bps: u16,
+    /// How long after the battle ends winners and refunds can still be paid;
+    /// after that the market can be closed and whatever is left swept to the
+    /// treasury. See `BattleState::claim_deadline`. Zero uses the protocol's
+    /// `default_claim_window_secs`, filled in when the market is created.
+    pub claim_window_secs: i64,
+}
+
+impl MarketConfig {
+    pub const LEN: usize = 8 + 1 + 8 + 1 + 1 + 8 + 8 + 8 + 2 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockReason {
+    TurnLimit,
+    AliveThreshold,
+    NearMaxDuration,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct MarketLockStatus {
+    pub bets_locked: bool,
+    pub sells_locked: bool,
+    pub reason: Option<LockReason>,
 }
@@ -2,8 +2,22 @@ pub mod battle;
 pub mod market;
 pub mod position;
 pub mod global;
+pub mod order_queue;
+pub mod liquidity;
+pub mod exotic;
+pub mod order_book;
+pub mod config;
+pub mod arena;
+pub mod template;
 
 pub use battle::*;
 pub use market::*;
 pub use position::*;
 pub use global::*;
+pub use order_queue::*;
+pub use liquidity::*;
+pub use exotic::*;
+pub use order_book::*;
+pub use config::*;
+pub use arena::*;
+pub use template::*;
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+/// Resting limit orders for one market. Fills happen against the curve
+/// whenever a trade or turn moves a creature's price through a limit.
+#[account]
+pub struct OrderBook {
+    pub battle_id: u64,
+    pub next_order_id: u64,
+    /// Whoever posted the first order, and so paid the rent.
+    pub payer: Pubkey,
+    pub orders: Vec<LimitOrder>,
+    pub bump: u8,
+}
+
+impl OrderBook {
+    pub const MAX_ORDERS: usize = 32;
+    /// Fills one crank makes at most, so a trade's compute stays bounded.
+    pub const MAX_FILLS_PER_CRANK: usize = 8;
+    pub const LEN: usize = 8 + 8 + 8 + 32 + (4 + LimitOrder::LEN * Self::MAX_ORDERS) + 1 + 50;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct LimitOrder {
+    pub id: u64,
+    pub owner: Pubkey,
+    pub creature_index: u8,
+    pub is_buy: bool,
+    /// Limit in the units of `get_share_price`: lamports per
+    /// `MarketState::price_scale` shares.
+    pub limit_price: u64,
+    /// Lamports still escrowed for a buy, shares still escrowed for a sell.
+    pub remaining: u64,
+    /// Shares bought so far, or lamports received so far for a sell.
+    pub filled: u64,
+    /// Cost basis of the shares bought so far, or of the shares a sell still
+    /// escrows.
+    pub cost_basis: u64,
+    /// Owner's position when a buy was posted; fills are held to the
+    /// market's position limit against it, since the crank can't see the
+    /// position itself.
+    pub position_at_post: u64,
+}
+
+impl LimitOrder {
+    pub const LEN: usize = 8 + 32 + 1 + 1 + 8 + 8 + 8 + 8 + 8;
+}
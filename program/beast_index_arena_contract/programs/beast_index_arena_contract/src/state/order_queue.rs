@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+/// Orders waiting for the next turn in a batch-mode market.
+#[account]
+pub struct OrderQueue {
+    pub battle_id: u64,
+    pub batch_number: u64,
+    /// Whoever posted the first order, and so paid the rent.
+    pub payer: Pubkey,
+    pub orders: Vec<BatchOrder>,
+    pub bump: u8,
+}
+
+impl OrderQueue {
+    pub const MAX_ORDERS: usize = 32;
+    pub const LEN: usize = 8 + 8 + 8 + 32 + (4 + BatchOrder::LEN * Self::MAX_ORDERS) + 1 + 50;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BatchOrder {
+    pub user: Pubkey,
+    pub creature_index: u8,
+    pub is_buy: bool,
+    /// Lamports escrowed for a buy, shares escrowed for a sell.
+    pub amount: u64,
+    /// Shares owed to a buyer, lamports owed to a seller once cleared.
+    pub fill: u64,
+    pub is_cleared: bool,
+    /// Cost basis of a buy's fill (net of the fee) once cleared, or of the
+    /// shares a sell escrowed.
+    pub cost_basis: u64,
+}
+
+impl BatchOrder {
+    pub const LEN: usize = 32 + 1 + 1 + 8 + 8 + 1 + 8;
+}
@@ -1,15 +1,54 @@
 use anchor_lang::prelude::*;
+use crate::errors::GameError;
+use crate::utils::share_of_cost_basis;
 
 #[account]
 pub struct UserPosition {
     pub user: Pubkey,
     pub battle_id: u64,
+    pub arena: Pubkey,
     pub creature_index: u8,
     pub amount: u64,
     pub claimed: bool,
     pub bump: u8,
+    /// What went into the curve for `amount`, net of fees; a refund pays
+    /// this back.
+    pub cost_basis: u64,
 }
 
 impl UserPosition {
-    pub const LEN: usize = 8 + 32 + 8 + 1 + 8 + 1 + 1 + 50;
+    pub const LEN: usize = 8 + 32 + 8 + 32 + 1 + 8 + 1 + 1 + 8 + 50;
+
+    pub fn add_shares(&mut self, shares: u64, cost: u64) -> Result<()> {
+        self.amount = self
+            .amount
+            .checked_add(shares)
+            .ok_or(GameError::CalculationOverflow)?;
+        self.cost_basis = self
+            .cost_basis
+            .checked_add(cost)
+            .ok_or(GameError::CalculationOverflow)?;
+        Ok(())
+    }
+
+    /// Adds shares that come back from an order. A claimed position was
+    /// already paid for what it held, so it reopens holding only these.
+    pub fn deliver_shares(&mut self, shares: u64, cost: u64) -> Result<()> {
+        if self.claimed {
+            self.amount = 0;
+            self.cost_basis = 0;
+            self.claimed = false;
+        }
+        self.add_shares(shares, cost)
+    }
+
+    /// Takes `shares` out along with their part of the cost basis, which is
+    /// returned.
+    pub fn remove_shares(&mut self, shares: u64) -> Result<u64> {
+        require!(shares <= self.amount, GameError::InsufficientShares);
+        let cost = share_of_cost_basis(self.cost_basis, self.amount, shares)?;
+        self.amount -= shares;
+        self.cost_basis -= cost;
+        Ok(cost)
+    }
 }
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::errors::GameError;
+use crate::state::MarketConfig;
+
+/// How a battle that runs out its `max_duration` is decided.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tiebreak {
+    /// Nobody wins.
+    #[default]
+    Draw,
+    /// The living creature with the most HP left wins; a tie for the most is a draw.
+    HighestHp,
+}
+
+/// Everything a battle created from a template starts with. Zero timing or a
+/// zero ability multiplier uses the protocol default.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct TemplateParams {
+    pub creature_hp: [u16; 4],
+    pub creature_atk: [u16; 4],
+    pub creature_def: [u16; 4],
+    pub creature_spd: [u16; 4],
+    /// Damage multiplier for each `Ability`, in basis points of the base damage.
+    pub ability_multipliers_bps: [u16; 3],
+    pub turn_interval: i64,
+    pub max_duration: i64,
+    /// Betting time before the first turn when no start time is given.
+    pub betting_window_secs: i64,
+    pub tiebreak: Tiebreak,
+    /// Used by markets opened for the template's battles without a config.
+    pub market_config: MarketConfig,
+}
+
+impl TemplateParams {
+    pub const LEN: usize = (2 * 4) * 4 + (2 * 3) + 8 + 8 + 8 + 1 + MarketConfig::LEN;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.creature_hp.iter().all(|&hp| hp > 0)
+                && self.turn_interval >= 0
+                && self.max_duration >= 0
+                && self.betting_window_secs >= 0
+                && self.market_config.lock_before_end_secs >= 0
+                && self.market_config.claim_window_secs >= 0,
+            GameError::InvalidTemplate
+        );
+        Ok(())
+    }
+}
+
+/// A reusable rule preset. Battles copy what they need at creation and record
+/// the template id and version they came from.
+#[account]
+pub struct BattleTemplate {
+    pub template_id: u64,
+    /// Bumped on every update.
+    pub version: u32,
+    pub params: TemplateParams,
+    pub bump: u8,
+}
+
+impl BattleTemplate {
+    pub const LEN: usize = 8 + 8 + 4 + TemplateParams::LEN + 1 + 100;
+}
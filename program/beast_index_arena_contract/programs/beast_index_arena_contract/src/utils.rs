@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::state::Ability;
+use crate::state::{
+    Ability, BattleState, ExoticKind, LineUnit, LockReason, MarketConfig, MarketLockStatus, MarketState,
+    OrderBook, OverUnderLine,
+};
 use crate::errors::GameError;
 
 pub fn get_random_seed(clock: &Clock, salt: u64) -> u64 {
@@ -10,8 +13,12 @@ pub fn get_random_seed(clock: &Clock, salt: u64) -> u64 {
     let mixed2 = timestamp.wrapping_mul(0x517cc1b727220a95);
     let mixed3 = salt.wrapping_mul(0x85ebca77c2b2ae63);
 
-    let result = mixed1 ^ mixed2 ^ mixed3;
-    result.wrapping_mul(result >> 32).wrapping_add(salt)
+    let mut result = mixed1 ^ mixed2 ^ mixed3;
+    result ^= result >> 30;
+    result = result.wrapping_mul(0xbf58476d1ce4e5b9);
+    result ^= result >> 27;
+    result = result.wrapping_mul(0x94d049bb133111eb);
+    result ^ (result >> 31)
 }
 
 pub fn pick_random_target(
@@ -20,11 +27,11 @@ pub fn pick_random_target(
     random_seed: u64,
 ) -> Option<usize> {
     let mut valid_targets = Vec::new();
-    for i in 0..4 {
+    for (i, &alive) in is_alive.iter().enumerate() {
         if i == attacker_idx {
             continue;
         }
-        if !is_alive[i] {
+        if !alive {
             continue;
         }
         valid_targets.push(i);
@@ -38,6 +45,26 @@ pub fn pick_random_target(
     Some(valid_targets[random_index])
 }
 
+/// Orders living creatures by SPD (fastest first). Ties are broken by a
+/// per-creature roll derived from `random_seed`, so equal-speed creatures
+/// don't always act in index order.
+pub fn order_by_initiative(
+    creature_spd: &[u16; 4],
+    is_alive: &[bool; 4],
+    random_seed: u64,
+) -> Vec<usize> {
+    let mut creature_order: Vec<(usize, u16, u64)> = Vec::new();
+    for (i, &alive) in is_alive.iter().enumerate() {
+        if alive {
+            let roll = (random_seed ^ (i as u64).wrapping_mul(0x9e3779b97f4a7c15))
+                .wrapping_mul(0xbf58476d1ce4e5b9);
+            creature_order.push((i, creature_spd[i], roll ^ (roll >> 31)));
+        }
+    }
+    creature_order.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)));
+    creature_order.into_iter().map(|(idx, _, _)| idx).collect()
+}
+
 pub fn pick_random_ability(random_seed: u64) -> Ability {
     let choice = random_seed % 3;
     match choice {
@@ -48,14 +75,11 @@ pub fn pick_random_ability(random_seed: u64) -> Ability {
     }
 }
 
-pub fn calculate_damage(atk: u16, def: u16, ability: Ability) -> u16 {
-    let base_damage = atk.saturating_sub(def);
-    let modified_damage = match ability {
-        Ability::BasicHit => base_damage,
-        Ability::HeavyStrike => base_damage * 3 / 2,
-        Ability::QuickJab => base_damage * 3 / 4,
-    };
-    modified_damage.max(1)
+/// Base damage scaled by the ability's multiplier from the protocol config.
+pub fn calculate_damage(atk: u16, def: u16, multiplier_bps: u16) -> u16 {
+    let base_damage = atk.saturating_sub(def) as u32;
+    let modified_damage = base_damage * multiplier_bps as u32 / 10_000;
+    (modified_damage.min(u16::MAX as u32) as u16).max(1)
 }
 
 pub fn calculate_buy_shares(
@@ -100,16 +124,855 @@ pub fn calculate_sell_price(
     Ok(sol_returned)
 }
 
-pub fn get_share_price(pool: u64, shares: u64) -> Result<u64> {
+/// Clears one creature's batch at a single price. Buyers and sellers cross
+/// with each other first and only the imbalance trades on the curve, sized so
+/// the curve's average price equals the crossing price.
+///
+/// Returns `(shares_to_buyers, lamports_to_sellers)`.
+pub fn clear_batch(
+    current_shares: u64,
+    k_constant: u128,
+    buy_lamports: u64,
+    sell_shares: u64,
+) -> Result<(u64, u64)> {
+    let buy = buy_lamports as u128;
+    let sell = sell_shares as u128;
+
+    // Net buy: buyers send `net` lamports to the curve and take every sold share.
+    let curve_shares_out = |net: u64| calculate_buy_shares(current_shares, net, k_constant).unwrap_or(0);
+    let net_buy = binary_search_max(buy_lamports, |net| {
+        let shares_out = curve_shares_out(net) as u128;
+        net == 0 || (shares_out > 0 && (buy - net as u128).saturating_mul(shares_out) >= sell.saturating_mul(net as u128))
+    });
+    if net_buy > 0 {
+        let shares_to_buyers = sell_shares
+            .checked_add(curve_shares_out(net_buy))
+            .ok_or(GameError::CalculationOverflow)?;
+        return Ok((shares_to_buyers, buy_lamports - net_buy));
+    }
+
+    // Net sell: sellers send `net` shares to the curve and take every lamport bet.
+    let curve_lamports_out = |net: u64| calculate_sell_price(current_shares, net, k_constant).unwrap_or(0);
+    let net_sell = binary_search_max(sell_shares, |net| {
+        let lamports_out = curve_lamports_out(net) as u128;
+        net == 0 || (sell - net as u128).saturating_mul(lamports_out) >= buy.saturating_mul(net as u128)
+    });
+    let shares_to_buyers = sell_shares - net_sell;
+    let lamports_to_sellers = buy_lamports
+        .checked_add(curve_lamports_out(net_sell))
+        .ok_or(GameError::CalculationOverflow)?;
+
+    // The curve couldn't take the imbalance, so one side would get nothing.
+    if (buy_lamports > 0 && shares_to_buyers == 0) || (sell_shares > 0 && lamports_to_sellers == 0) {
+        return Err(GameError::CalculationOverflow.into());
+    }
+
+    Ok((shares_to_buyers, lamports_to_sellers))
+}
+
+/// Largest `x` in `0..=upper` for which `fits(x)` holds, assuming `fits` is
+/// true up to some point and false after it.
+fn binary_search_max(upper: u64, fits: impl Fn(u64) -> bool) -> u64 {
+    let (mut low, mut high) = (0u64, upper);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if fits(mid) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    low
+}
+
+/// Lamports per `price_scale` shares (see `MarketState::price_scale`).
+pub fn get_share_price(pool: u64, shares: u64, price_scale: u64) -> Result<u64> {
     if shares == 0 {
         return Ok(0);
     }
 
     let price = (pool as u128)
-        .checked_mul(1_000_000_000)
+        .checked_mul(price_scale as u128)
         .ok_or(GameError::CalculationOverflow)?
         .checked_div(shares as u128)
         .ok_or(GameError::DivisionByZero)? as u64;
 
     Ok(price)
 }
+
+pub fn get_lock_reason(config: &MarketConfig, battle: &BattleState, now: i64) -> Option<LockReason> {
+    if config.lock_after_turn > 0 && battle.current_turn >= config.lock_after_turn {
+        return Some(LockReason::TurnLimit);
+    }
+
+    let alive_count = battle.is_alive.iter().filter(|&&alive| alive).count() as u8;
+    if config.lock_at_alive_count > 0 && alive_count <= config.lock_at_alive_count {
+        return Some(LockReason::AliveThreshold);
+    }
+
+    if config.lock_before_end_secs > 0 {
+        let lock_time = battle
+            .start_time
+            .saturating_add(battle.max_duration)
+            .saturating_sub(config.lock_before_end_secs);
+        if now >= lock_time {
+            return Some(LockReason::NearMaxDuration);
+        }
+    }
+
+    None
+}
+
+pub fn get_lock_status(config: &MarketConfig, battle: &BattleState, now: i64) -> MarketLockStatus {
+    let reason = get_lock_reason(config, battle, now);
+    MarketLockStatus {
+        bets_locked: reason.is_some(),
+        sells_locked: reason.is_some() && !config.allow_sells_when_locked,
+        reason,
+    }
+}
+
+/// Enforces the market's exposure limits on a buy of `shares_bought` for
+/// `amount`. `position_after` is the buyer's holding on the creature after it.
+pub fn check_buy_limits(
+    market: &MarketState,
+    amount: u64,
+    current_shares: u64,
+    shares_bought: u64,
+    position_after: u64,
+) -> Result<()> {
+    let config = &market.config;
+    require!(
+        config.max_bet == 0 || amount <= config.max_bet,
+        GameError::BetTooLarge
+    );
+    require!(
+        config.max_position == 0 || position_after <= config.max_position,
+        GameError::PositionLimitExceeded
+    );
+    require!(
+        config.max_total_pool == 0 || market.total_pool.saturating_add(amount) <= config.max_total_pool,
+        GameError::PoolLimitExceeded
+    );
+    check_price_impact(config, current_shares, shares_bought)
+}
+
+/// Part of `cost` that belongs to `shares` out of a holding of `amount`;
+/// taking all of them takes all of it, so nothing is left over from rounding.
+pub fn share_of_cost_basis(cost: u64, amount: u64, shares: u64) -> Result<u64> {
+    if shares >= amount {
+        return Ok(cost);
+    }
+    Ok(((cost as u128)
+        .checked_mul(shares as u128)
+        .ok_or(GameError::CalculationOverflow)?
+        / amount as u128) as u64)
+}
+
+/// Refund for a position's `cost_basis` in a refunding market: all of it while
+/// the creature's pool covers every outstanding basis, otherwise a pro-rata
+/// cut of the pool, so it doesn't matter who claims first.
+pub fn get_position_refund(market: &MarketState, creature_index: u8, cost_basis: u64) -> Result<u64> {
+    let pool = market.creature_pool(creature_index)?;
+    let outstanding = market.cost_basis_outstanding[creature_index as usize];
+    if pool >= outstanding {
+        return Ok(cost_basis.min(pool));
+    }
+    let refund = (cost_basis as u128)
+        .checked_mul(pool as u128)
+        .ok_or(GameError::CalculationOverflow)?
+        / outstanding as u128;
+    Ok((refund as u64).min(pool))
+}
+
+/// Refund for `balance` outcome tokens in a refunding market: their pro-rata
+/// part of the creature's pool, so the last holder to redeem empties it.
+pub fn get_outcome_token_refund(market: &MarketState, creature_index: u8, balance: u64) -> Result<u64> {
+    let outstanding_shares = market
+        .initial_liquidity
+        .checked_sub(market.creature_shares(creature_index)?)
+        .ok_or(GameError::CalculationOverflow)?;
+    Ok((balance as u128)
+        .checked_mul(market.creature_pool(creature_index)? as u128)
+        .ok_or(GameError::CalculationOverflow)?
+        .checked_div(outstanding_shares as u128)
+        .ok_or(GameError::DivisionByZero)? as u64)
+}
+
+/// A trade's impact is the share of the creature's remaining curve it moves.
+pub fn check_price_impact(config: &MarketConfig, current_shares: u64, shares_moved: u64) -> Result<()> {
+    require!(
+        config.max_price_impact_bps == 0
+            || (shares_moved as u128) * 10_000
+                <= (current_shares as u128) * config.max_price_impact_bps as u128,
+        GameError::PriceImpactTooHigh
+    );
+    Ok(())
+}
+
+/// What a finished market still owes bettors: unclaimed winnings, pools
+/// awaiting refunds and batch sell fills that haven't been settled.
+pub fn get_market_liability(market: &MarketState, battle: &BattleState) -> Result<u64> {
+    let owed_to_bettors = if market.is_refunding(battle) {
+        market.total_pool
+    } else if let Some(winner) = battle.winner {
+        let (winning_pool, winning_shares) = match winner {
+            0 => (market.creature_0_pool, market.creature_0_shares),
+            1 => (market.creature_1_pool, market.creature_1_shares),
+            2 => (market.creature_2_pool, market.creature_2_shares),
+            3 => (market.creature_3_pool, market.creature_3_shares),
+            _ => return Err(GameError::InvalidCreatureIndex.into()),
+        };
+        let unclaimed_shares = market
+            .initial_liquidity
+            .saturating_sub(winning_shares)
+            .saturating_sub(market.winning_shares_claimed);
+        if unclaimed_shares == 0 {
+            0
+        } else {
+            (unclaimed_shares as u128)
+                .checked_mul(market.total_pool as u128)
+                .ok_or(GameError::CalculationOverflow)?
+                .checked_div(winning_pool as u128)
+                .ok_or(GameError::DivisionByZero)? as u64
+        }
+    } else {
+        0
+    };
+
+    owed_to_bettors
+        .checked_add(market.batch_fills_owed)
+        .ok_or(GameError::CalculationOverflow.into())
+}
+
+/// What the LPs' side of a live market is worth: everything it can pay out
+/// beyond the bettors' stakes and unsettled batch fills, so fees earned so far
+/// count too.
+pub fn get_lp_value(market: &MarketState, available: u64) -> u64 {
+    available
+        .saturating_sub(market.total_pool)
+        .saturating_sub(market.batch_fills_owed)
+}
+
+/// Deepens every creature's curve by `amount` and mints LP shares for it at
+/// the current `lp_value` (see `get_lp_value`, taken before the deposit).
+/// Only allowed while no bets are open.
+pub fn add_market_liquidity(market: &mut MarketState, amount: u64, lp_value: u64) -> Result<u64> {
+    let lp_shares = if market.total_lp_shares == 0 || lp_value == 0 {
+        amount
+    } else {
+        (amount as u128)
+            .checked_mul(market.total_lp_shares as u128)
+            .ok_or(GameError::CalculationOverflow)?
+            .checked_div(lp_value as u128)
+            .ok_or(GameError::DivisionByZero)? as u64
+    };
+    require!(lp_shares > 0, GameError::ZeroShares);
+    // Deepening the curves would reprice shares already sold.
+    require!(market.total_pool == 0, GameError::LiquidityWindowClosed);
+
+    market.creature_0_shares = market.creature_0_shares.checked_add(amount).ok_or(GameError::CalculationOverflow)?;
+    market.creature_1_shares = market.creature_1_shares.checked_add(amount).ok_or(GameError::CalculationOverflow)?;
+    market.creature_2_shares = market.creature_2_shares.checked_add(amount).ok_or(GameError::CalculationOverflow)?;
+    market.creature_3_shares = market.creature_3_shares.checked_add(amount).ok_or(GameError::CalculationOverflow)?;
+    market.initial_liquidity = market.initial_liquidity.checked_add(amount).ok_or(GameError::CalculationOverflow)?;
+    market.k_constant = (market.initial_liquidity as u128).pow(2);
+
+    market.lp_deposit = market.lp_deposit.checked_add(amount).ok_or(GameError::CalculationOverflow)?;
+    market.total_lp_shares = market.total_lp_shares.checked_add(lp_shares).ok_or(GameError::CalculationOverflow)?;
+
+    Ok(lp_shares)
+}
+
+/// Amount owed for redeeming `lp_shares` from a finished market: a pro rata
+/// cut of everything it can pay out beyond what bettors are still owed.
+pub fn get_lp_redemption(
+    market: &MarketState,
+    battle: &BattleState,
+    available: u64,
+    lp_shares: u64,
+) -> Result<u64> {
+    let house_value = available
+        .checked_sub(get_market_liability(market, battle)?)
+        .ok_or(GameError::MarketInsolvent)?;
+
+    Ok((house_value as u128)
+        .checked_mul(lp_shares as u128)
+        .ok_or(GameError::CalculationOverflow)?
+        .checked_div(market.total_lp_shares as u128)
+        .ok_or(GameError::DivisionByZero)? as u64)
+}
+
+/// Fills resting limit orders against the curve, each only as far as the
+/// creature's price stays within its limit and the market's trade limits
+/// allow. Buys pay the trade fee like any other bet, skip dead creatures, and
+/// either side is skipped while locked. At most
+/// `OrderBook::MAX_FILLS_PER_CRANK` orders fill per call.
+///
+/// Returns `(lamports_into_market, lamports_out_of_market)` so the caller can
+/// move the escrow between the book and the market.
+pub fn fill_limit_orders(
+    market: &mut MarketState,
+    book: &mut OrderBook,
+    battle: &BattleState,
+    lock_status: &MarketLockStatus,
+) -> Result<(u64, u64)> {
+    let mut lamports_in: u64 = 0;
+    let mut lamports_out: u64 = 0;
+    let mut fills = 0;
+
+    for order in book.orders.iter_mut().filter(|order| order.remaining > 0) {
+        if fills == OrderBook::MAX_FILLS_PER_CRANK {
+            break;
+        }
+        let creature_index = order.creature_index;
+        let pool = market.creature_pool(creature_index)?;
+        let shares = market.creature_shares(creature_index)?;
+        let k = market.k_constant;
+        let limit = order.limit_price;
+        let price = get_share_price(pool, shares, market.price_scale)?;
+
+        if order.is_buy {
+            if lock_status.bets_locked || !battle.is_alive[creature_index as usize] || price > limit {
+                continue;
+            }
+            let position_before = order.position_at_post.saturating_add(order.filled);
+            let quote = |lamports: u64| {
+                let net = market.split_trade_fee(lamports).map(|(net, _)| net).unwrap_or(0);
+                (net, calculate_buy_shares(shares, net, k).unwrap_or(0))
+            };
+            let amount = binary_search_max(order.remaining, |lamports| {
+                let (net, out) = quote(lamports);
+                lamports == 0
+                    || (out > 0
+                        && check_buy_limits(market, lamports, shares, out, position_before.saturating_add(out)).is_ok()
+                        && get_share_price(pool.saturating_add(net), shares - out, market.price_scale).unwrap_or(u64::MAX) <= limit)
+            });
+            if amount == 0 {
+                continue;
+            }
+            let (net, bought) = quote(amount);
+            market.record_buy(creature_index, net, bought)?;
+            market.add_cost_basis(creature_index, net)?;
+            order.remaining -= amount;
+            order.filled = order.filled.checked_add(bought).ok_or(GameError::CalculationOverflow)?;
+            order.cost_basis = order.cost_basis.checked_add(net).ok_or(GameError::CalculationOverflow)?;
+            lamports_in = lamports_in.checked_add(amount).ok_or(GameError::CalculationOverflow)?;
+        } else {
+            if lock_status.sells_locked || price < limit {
+                continue;
+            }
+            let config = market.config;
+            let proceeds_for = |sold: u64| calculate_sell_price(shares, sold, k).unwrap_or(0);
+            let sold = binary_search_max(order.remaining, |sold| {
+                let proceeds = proceeds_for(sold);
+                sold == 0
+                    || (proceeds > 0
+                        && check_price_impact(&config, shares, sold).is_ok()
+                        && proceeds <= pool
+                        && get_share_price(pool - proceeds, shares.saturating_add(sold), market.price_scale).unwrap_or(0) >= limit)
+            });
+            if sold == 0 {
+                continue;
+            }
+            let proceeds = proceeds_for(sold);
+            market.record_sell(creature_index, proceeds, sold)?;
+            let cost = share_of_cost_basis(order.cost_basis, order.remaining, sold)?;
+            market.remove_cost_basis(creature_index, cost)?;
+            order.cost_basis -= cost;
+            order.remaining -= sold;
+            order.filled = order.filled.checked_add(proceeds).ok_or(GameError::CalculationOverflow)?;
+            lamports_out = lamports_out.checked_add(proceeds).ok_or(GameError::CalculationOverflow)?;
+        }
+
+        fills += 1;
+        msg!(
+            "Limit order {} on Creature {} filled, {} left",
+            order.id,
+            creature_index,
+            order.remaining
+        );
+    }
+
+    Ok((lamports_in, lamports_out))
+}
+
+/// Maps a pick to its outcome index. Finishing-order picks are ranked as
+/// permutations (first place most significant); single-creature markets use
+/// `selection[0]` only.
+pub fn encode_selection(kind: ExoticKind, selection: [u8; 3]) -> Result<u8> {
+    let places = match kind {
+        ExoticKind::Exacta => 2,
+        ExoticKind::Trifecta => 3,
+        ExoticKind::FirstBlood | ExoticKind::NextDeath | ExoticKind::OverUnder => 1,
+    };
+    if kind == ExoticKind::OverUnder {
+        require!(selection[0] < 2, GameError::InvalidSelection);
+        return Ok(selection[0]);
+    }
+
+    let mut outcome = 0u8;
+    let mut remaining = 4u8;
+    for (place, &creature) in selection.iter().take(places).enumerate() {
+        require!(creature < 4, GameError::InvalidSelection);
+        require!(
+            !selection[..place].contains(&creature),
+            GameError::InvalidSelection
+        );
+        let rank = creature - selection[..place].iter().filter(|&&c| c < creature).count() as u8;
+        outcome = outcome * remaining + rank;
+        remaining -= 1;
+    }
+    Ok(outcome)
+}
+
+/// Result of one exotic round given the battle so far: `None` while it is
+/// still open, `Some(None)` when it refunds, `Some(Some(outcome))` once decided.
+pub fn get_exotic_result(
+    kind: ExoticKind,
+    line: &OverUnderLine,
+    round: u8,
+    battle: &BattleState,
+) -> Result<Option<Option<u8>>> {
+    if battle.is_cancelled {
+        return Ok(Some(None));
+    }
+
+    let result = match kind {
+        ExoticKind::FirstBlood | ExoticKind::NextDeath => {
+            if battle.death_count > round {
+                Some(Some(battle.death_order[round as usize]))
+            } else if battle.is_battle_over {
+                Some(None)
+            } else {
+                None
+            }
+        }
+        ExoticKind::Exacta | ExoticKind::Trifecta => {
+            if !battle.is_battle_over {
+                None
+            } else if let (Some(winner), 3) = (battle.winner, battle.death_count) {
+                let finish = [winner, battle.death_order[2], battle.death_order[1]];
+                Some(Some(encode_selection(kind, finish)?))
+            } else {
+                // Draws and timeouts have no full finishing order.
+                Some(None)
+            }
+        }
+        ExoticKind::OverUnder => {
+            if !battle.is_battle_over {
+                None
+            } else {
+                // Seconds exclude paused time, since resuming shifts both timestamps.
+                let length = match line.unit {
+                    LineUnit::Turns => battle.current_turn,
+                    LineUnit::Seconds => battle.last_turn_time.saturating_sub(battle.start_time) as u64,
+                };
+                match length.cmp(&line.value) {
+                    std::cmp::Ordering::Less => Some(Some(0)),
+                    std::cmp::Ordering::Greater => Some(Some(1)),
+                    std::cmp::Ordering::Equal => Some(None),
+                }
+            }
+        }
+    };
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::UserPosition;
+
+    const LIQUIDITY: u64 = 10_000_000_000;
+
+    #[test]
+    fn batch_clears_both_sides_at_one_price() {
+        let k = (LIQUIDITY as u128).pow(2);
+        for (buy, sell) in [(1_000_000_000, 300_000_000), (200_000_000, 900_000_000)] {
+            let (to_buyers, to_sellers) = clear_batch(LIQUIDITY, k, buy, sell).unwrap();
+            let buy_price = buy as f64 / to_buyers as f64;
+            let sell_price = to_sellers as f64 / sell as f64;
+            assert!(
+                (buy_price - sell_price).abs() / buy_price < 0.001,
+                "buy price {} vs sell price {}",
+                buy_price,
+                sell_price
+            );
+        }
+    }
+
+    #[test]
+    fn one_sided_batch_trades_on_the_curve() {
+        let k = (LIQUIDITY as u128).pow(2);
+        let amount = 500_000_000;
+        assert_eq!(
+            clear_batch(LIQUIDITY, k, amount, 0).unwrap(),
+            (calculate_buy_shares(LIQUIDITY, amount, k).unwrap(), 0)
+        );
+        assert_eq!(
+            clear_batch(LIQUIDITY, k, 0, amount).unwrap(),
+            (0, calculate_sell_price(LIQUIDITY, amount, k).unwrap())
+        );
+    }
+
+    #[test]
+    fn finishing_orders_encode_to_distinct_outcomes() {
+        let mut seen = std::collections::HashSet::new();
+        for a in 0..4 {
+            for b in 0..4 {
+                for c in 0..4 {
+                    if a != b && b != c && a != c {
+                        let outcome = encode_selection(ExoticKind::Trifecta, [a, b, c]).unwrap();
+                        assert!((outcome as usize) < ExoticKind::Trifecta.outcomes());
+                        seen.insert(outcome);
+                    }
+                }
+            }
+        }
+        assert_eq!(seen.len(), 24);
+        assert!(encode_selection(ExoticKind::Exacta, [2, 2, 0]).is_err());
+    }
+
+    #[test]
+    fn exotic_rounds_settle_on_deaths() {
+        let line = OverUnderLine::default();
+        let mut battle = BattleState {
+            death_order: [2, 0, 3, 0],
+            death_count: 1,
+            ..Default::default()
+        };
+        assert_eq!(get_exotic_result(ExoticKind::FirstBlood, &line, 0, &battle).unwrap(), Some(Some(2)));
+        assert_eq!(get_exotic_result(ExoticKind::NextDeath, &line, 1, &battle).unwrap(), None);
+        assert_eq!(get_exotic_result(ExoticKind::Exacta, &line, 0, &battle).unwrap(), None);
+
+        battle.death_count = 3;
+        battle.is_battle_over = true;
+        battle.winner = Some(1);
+        assert_eq!(
+            get_exotic_result(ExoticKind::Trifecta, &line, 0, &battle).unwrap(),
+            Some(Some(encode_selection(ExoticKind::Trifecta, [1, 3, 0]).unwrap()))
+        );
+    }
+
+    #[test]
+    fn over_under_pushes_on_an_exact_hit() {
+        let line = OverUnderLine { value: 12, unit: LineUnit::Turns };
+        let mut battle = BattleState {
+            is_battle_over: true,
+            current_turn: 12,
+            ..Default::default()
+        };
+        assert_eq!(get_exotic_result(ExoticKind::OverUnder, &line, 0, &battle).unwrap(), Some(None));
+        battle.current_turn = 13;
+        assert_eq!(get_exotic_result(ExoticKind::OverUnder, &line, 0, &battle).unwrap(), Some(Some(1)));
+
+        let line = OverUnderLine { value: 60, unit: LineUnit::Seconds };
+        battle.start_time = 1_000;
+        battle.last_turn_time = 1_045;
+        assert_eq!(get_exotic_result(ExoticKind::OverUnder, &line, 0, &battle).unwrap(), Some(Some(0)));
+    }
+
+    #[test]
+    fn limit_orders_fill_only_up_to_their_limit() {
+        use crate::state::{LimitOrder, MarketLockStatus};
+
+        let mut market = MarketState {
+            creature_0_shares: LIQUIDITY,
+            creature_1_shares: LIQUIDITY,
+            creature_2_shares: LIQUIDITY,
+            creature_3_shares: LIQUIDITY,
+            k_constant: (LIQUIDITY as u128).pow(2),
+            initial_liquidity: LIQUIDITY,
+            price_scale: 1_000_000_000,
+            ..Default::default()
+        };
+        let battle = BattleState {
+            is_alive: [true; 4],
+            ..Default::default()
+        };
+        let limit = 100_000_000;
+        let mut book = OrderBook {
+            battle_id: 0,
+            next_order_id: 1,
+            payer: Pubkey::default(),
+            orders: vec![LimitOrder {
+                id: 0,
+                owner: Pubkey::default(),
+                creature_index: 2,
+                is_buy: true,
+                limit_price: limit,
+                remaining: 5_000_000_000,
+                filled: 0,
+                cost_basis: 0,
+                position_at_post: 0,
+            }],
+            bump: 0,
+        };
+        let unlocked = MarketLockStatus {
+            bets_locked: false,
+            sells_locked: false,
+            reason: None,
+        };
+
+        let (lamports_in, lamports_out) = fill_limit_orders(&mut market, &mut book, &battle, &unlocked).unwrap();
+        let order = book.orders[0];
+        assert!(lamports_in > 0 && order.remaining > 0 && lamports_out == 0);
+        assert_eq!(lamports_in + order.remaining, 5_000_000_000);
+        assert_eq!(order.filled, LIQUIDITY - market.creature_2_shares);
+        assert!(get_share_price(market.creature_2_pool, market.creature_2_shares, market.price_scale).unwrap() <= limit);
+
+        // Nothing crosses on a second pass.
+        assert_eq!(fill_limit_orders(&mut market, &mut book, &battle, &unlocked).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn limit_buys_pay_the_fee_and_respect_the_position_limit() {
+        use crate::state::{LimitOrder, MarketLockStatus};
+
+        let mut market = MarketState {
+            creature_0_shares: LIQUIDITY,
+            creature_1_shares: LIQUIDITY,
+            creature_2_shares: LIQUIDITY,
+            creature_3_shares: LIQUIDITY,
+            k_constant: (LIQUIDITY as u128).pow(2),
+            initial_liquidity: LIQUIDITY,
+            trade_fee_bps: 100,
+            config: MarketConfig {
+                max_position: LIQUIDITY,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let battle = BattleState {
+            is_alive: [true; 4],
+            ..Default::default()
+        };
+        let order = |id: u64, creature_index: u8, remaining: u64, position_at_post: u64| LimitOrder {
+            id,
+            owner: Pubkey::default(),
+            creature_index,
+            is_buy: true,
+            limit_price: u64::MAX,
+            remaining,
+            filled: 0,
+            cost_basis: 0,
+            position_at_post,
+        };
+        // The first owner was already at the position limit when posting.
+        let mut orders = vec![order(0, 0, 100_000_000, LIQUIDITY)];
+        for remaining in [100_000_000, 1_000_000_000, 5_000_000_000] {
+            for creature_index in 1..4u8 {
+                orders.push(order(orders.len() as u64, creature_index, remaining, 0));
+            }
+        }
+        let mut book = OrderBook {
+            battle_id: 0,
+            next_order_id: orders.len() as u64,
+            payer: Pubkey::default(),
+            orders,
+            bump: 0,
+        };
+        let unlocked = MarketLockStatus {
+            bets_locked: false,
+            sells_locked: false,
+            reason: None,
+        };
+
+        // One crank fills at most eight orders, and the fee stays out of the curve.
+        let (lamports_in, _) = fill_limit_orders(&mut market, &mut book, &battle, &unlocked).unwrap();
+        assert_eq!(lamports_in, 3 * 100_000_000 + 3 * 1_000_000_000 + 2 * 5_000_000_000);
+        assert_eq!(market.creature_1_pool, 6_039_000_000);
+        assert_eq!(book.orders[1].cost_basis, 99_000_000);
+        assert_eq!(book.orders[0].remaining, 100_000_000);
+        assert_eq!(book.orders[9].remaining, 5_000_000_000);
+
+        let (lamports_in, _) = fill_limit_orders(&mut market, &mut book, &battle, &unlocked).unwrap();
+        assert_eq!(lamports_in, 5_000_000_000);
+        assert_eq!(book.orders[0].remaining, 100_000_000);
+    }
+
+    #[test]
+    fn each_exposure_limit_has_its_own_error() {
+        let mut market = MarketState {
+            total_pool: 900,
+            config: MarketConfig {
+                max_bet: 100,
+                max_position: 1_000,
+                max_total_pool: 1_000,
+                max_price_impact_bps: 500,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = |result: Result<()>| result.unwrap_err();
+
+        assert!(check_buy_limits(&market, 100, 10_000, 500, 1_000).is_ok());
+        assert_eq!(err(check_buy_limits(&market, 101, 10_000, 500, 1_000)), GameError::BetTooLarge.into());
+        assert_eq!(err(check_buy_limits(&market, 100, 10_000, 500, 1_001)), GameError::PositionLimitExceeded.into());
+        assert_eq!(err(check_buy_limits(&market, 100, 10_000, 501, 1_000)), GameError::PriceImpactTooHigh.into());
+        market.total_pool = 901;
+        assert_eq!(err(check_buy_limits(&market, 100, 10_000, 500, 1_000)), GameError::PoolLimitExceeded.into());
+    }
+
+    #[test]
+    fn lock_rules_trip_on_turn_deaths_and_time() {
+        let config = MarketConfig {
+            lock_after_turn: 5,
+            lock_at_alive_count: 2,
+            lock_before_end_secs: 100,
+            ..Default::default()
+        };
+        let mut battle = BattleState {
+            is_alive: [true; 4],
+            start_time: 1_000,
+            max_duration: 1_000,
+            ..Default::default()
+        };
+        assert_eq!(get_lock_reason(&config, &battle, 1_899), None);
+        assert_eq!(get_lock_reason(&config, &battle, 1_900), Some(LockReason::NearMaxDuration));
+
+        battle.is_alive = [true, true, false, false];
+        assert_eq!(get_lock_reason(&config, &battle, 1_000), Some(LockReason::AliveThreshold));
+
+        battle.current_turn = 5;
+        assert_eq!(get_lock_reason(&config, &battle, 1_000), Some(LockReason::TurnLimit));
+
+        // Zero disables a rule; sells only lock if the market says so
+        assert_eq!(get_lock_reason(&MarketConfig::default(), &battle, 1_900), None);
+        let status = get_lock_status(&config, &battle, 1_000);
+        assert!(status.bets_locked && status.sells_locked);
+        let status = get_lock_status(&MarketConfig { allow_sells_when_locked: true, ..config }, &battle, 1_000);
+        assert!(status.bets_locked && !status.sells_locked);
+    }
+
+    #[test]
+    fn cost_basis_leaves_with_the_shares_it_paid_for() {
+        assert_eq!(share_of_cost_basis(1_000, 300, 100).unwrap(), 333);
+        assert_eq!(share_of_cost_basis(1_000, 300, 300).unwrap(), 1_000);
+        assert_eq!(share_of_cost_basis(0, 300, 100).unwrap(), 0);
+
+        // Selling in pieces never strands basis on an empty position.
+        let mut position = UserPosition {
+            user: Pubkey::default(),
+            battle_id: 0,
+            arena: Pubkey::default(),
+            creature_index: 0,
+            amount: 0,
+            claimed: false,
+            bump: 0,
+            cost_basis: 0,
+        };
+        position.add_shares(300, 1_000).unwrap();
+        assert_eq!(position.remove_shares(100).unwrap(), 333);
+        assert_eq!(position.remove_shares(200).unwrap(), 667);
+        assert_eq!((position.amount, position.cost_basis), (0, 0));
+        assert!(position.remove_shares(1).is_err());
+
+        // Shares coming back to a paid-out position reopen it with just those.
+        position.add_shares(500, 400).unwrap();
+        position.claimed = true;
+        position.deliver_shares(100, 90).unwrap();
+        assert_eq!((position.amount, position.cost_basis, position.claimed), (100, 90, false));
+    }
+
+    #[test]
+    fn position_refunds_split_a_short_pool_pro_rata() {
+        let mut market = MarketState {
+            creature_0_pool: 1_000,
+            total_pool: 1_000,
+            cost_basis_outstanding: [1_500, 0, 0, 0],
+            ..Default::default()
+        };
+
+        // Bases of 900 and 600 against a pool of 1_000, in either order
+        let first = get_position_refund(&market, 0, 900).unwrap();
+        assert_eq!(first, 600);
+        market.record_sell(0, first, 0).unwrap();
+        market.remove_cost_basis(0, 900).unwrap();
+        let second = get_position_refund(&market, 0, 600).unwrap();
+        assert_eq!(second, 400);
+        market.record_sell(0, second, 0).unwrap();
+        market.remove_cost_basis(0, 600).unwrap();
+        assert_eq!((market.creature_0_pool, market.cost_basis_outstanding[0]), (0, 0));
+
+        // A pool that covers every basis pays each in full
+        market.creature_0_pool = 2_000;
+        market.cost_basis_outstanding[0] = 1_500;
+        assert_eq!(get_position_refund(&market, 0, 900).unwrap(), 900);
+    }
+
+    #[test]
+    fn outcome_token_refunds_split_the_pool_pro_rata() {
+        let mut market = MarketState {
+            initial_liquidity: LIQUIDITY,
+            creature_0_shares: LIQUIDITY - 300,
+            creature_0_pool: 1_000,
+            total_pool: 1_000,
+            ..Default::default()
+        };
+
+        // Holders of 100 and 200 tokens redeem one after the other
+        let first = get_outcome_token_refund(&market, 0, 100).unwrap();
+        assert_eq!(first, 333);
+        market.record_sell(0, first, 100).unwrap();
+        let second = get_outcome_token_refund(&market, 0, 200).unwrap();
+        assert_eq!(second, 667);
+        market.record_sell(0, second, 200).unwrap();
+        assert_eq!(market.creature_0_pool, 0);
+        assert_eq!(market.creature_0_shares, LIQUIDITY);
+    }
+
+    #[test]
+    fn lp_deposits_buy_in_at_pool_value_and_draws_refund() {
+        let mut market = MarketState {
+            creature_0_shares: LIQUIDITY,
+            creature_1_shares: LIQUIDITY,
+            creature_2_shares: LIQUIDITY,
+            creature_3_shares: LIQUIDITY,
+            k_constant: (LIQUIDITY as u128).pow(2),
+            initial_liquidity: LIQUIDITY,
+            lp_deposit: LIQUIDITY,
+            total_lp_shares: LIQUIDITY,
+            ..Default::default()
+        };
+
+        // Fees from bets since sold back have grown the LPs' side to 1.5x
+        // what they put in, so a new deposit of the same size gets two thirds
+        // of the shares.
+        let available = LIQUIDITY + LIQUIDITY / 2;
+        let lp_value = get_lp_value(&market, available);
+        assert_eq!(lp_value, LIQUIDITY + LIQUIDITY / 2);
+        let lp_shares = add_market_liquidity(&mut market, LIQUIDITY, lp_value).unwrap();
+        assert_eq!(lp_shares, LIQUIDITY * 2 / 3);
+        assert_eq!(market.creature_0_shares, 2 * LIQUIDITY);
+
+        // Once a bet is open, deepening the curves would reprice it.
+        market.total_pool = 3_000_000_000;
+        assert!(add_market_liquidity(&mut market, LIQUIDITY, lp_value).is_err());
+        assert_eq!(market.creature_0_shares, 2 * LIQUIDITY);
+
+        // A draw owes every bettor their stake back, like a cancelled battle.
+        let mut battle = BattleState {
+            is_battle_over: true,
+            winner: None,
+            ..Default::default()
+        };
+        assert!(market.is_refunding(&battle));
+        assert_eq!(get_market_liability(&market, &battle).unwrap(), market.total_pool);
+        battle.winner = Some(0);
+        assert!(!market.is_refunding(&battle));
+    }
+
+    #[test]
+    fn default_ability_multipliers_keep_the_original_damage() {
+        let params = crate::state::ProtocolParams::default();
+        for (atk, def) in [(50u16, 20u16), (101, 20), (20, 50)] {
+            let base = atk.saturating_sub(def);
+            let damage = |ability| calculate_damage(atk, def, params.ability_multiplier_bps(ability));
+            assert_eq!(damage(Ability::BasicHit), base.max(1));
+            assert_eq!(damage(Ability::HeavyStrike), (base * 3 / 2).max(1));
+            assert_eq!(damage(Ability::QuickJab), (base * 3 / 4).max(1));
+        }
+    }
+}
@@ -8,24 +8,281 @@ describe("Beast Index Arena - Complete Test Suite", () => {
   anchor.setProvider(provider);
   const program = anchor.workspace.BeastIndexArenaContract as Program<BeastIndexArenaContract>;
 
-  // Use battle ID 100 for production frontend (with proper 10 SOL liquidity)
-  const sharedBattleId = new anchor.BN(100);
-
-  // Generate unique battle IDs for other tests
-  const testRunId = Date.now();
-  let battleCounter = 0;
-  const getUniqueBattleId = () => {
-    return new anchor.BN(testRunId + battleCounter++);
+  const [globalState] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("global")],
+    program.programId
+  );
+
+  // A fresh arena per run, so battle ids start at 1 and never collide
+  const arenaName = `test-${Date.now()}`;
+  const [arena] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("arena"), Buffer.from(arenaName)],
+    program.programId
+  );
+
+  // Battle IDs are handed out by the arena's counter
+  let sharedBattleId: anchor.BN;
+  const getNextBattleId = async () => {
+    const arenaState = await program.account.arena.fetch(arena);
+    return arenaState.nextBattleId;
   };
 
+  // Market config with every lock rule and exposure limit disabled
+  const openMarketConfig = {
+    lockAfterTurn: new anchor.BN(0),
+    lockAtAliveCount: 0,
+    lockBeforeEndSecs: new anchor.BN(0),
+    allowSellsWhenLocked: true,
+    batchMode: false,
+    maxBet: new anchor.BN(0),
+    maxPosition: new anchor.BN(0),
+    maxTotalPool: new anchor.BN(0),
+    maxPriceImpactBps: 0,
+    claimWindowSecs: new anchor.BN(0),
+  };
+
+  // Same values the program used to hard-code
+  const defaultProtocolParams = {
+    minBet: new anchor.BN(10_000_000),
+    maxBet: new anchor.BN(0),
+    tradeFeeBps: 0,
+    defaultTurnInterval: new anchor.BN(10),
+    defaultMaxDuration: new anchor.BN(86400),
+    abilityMultipliersBps: [10_000, 15_000, 7_500],
+    priceScale: new anchor.BN(1_000_000_000),
+    defaultClaimWindowSecs: new anchor.BN(30 * 24 * 60 * 60),
+  };
+
+  // Same stats for every creature; zero multipliers use the protocol's
+  const templateParams = (hp: number, turnInterval: number) => ({
+    creatureHp: [hp, hp, hp, hp],
+    creatureAtk: [50, 50, 50, 50],
+    creatureDef: [20, 20, 20, 20],
+    creatureSpd: [30, 30, 30, 30],
+    abilityMultipliersBps: [0, 0, 0],
+    turnInterval: new anchor.BN(turnInterval),
+    maxDuration: new anchor.BN(86400),
+    bettingWindowSecs: new anchor.BN(0),
+    tiebreak: { draw: {} },
+    marketConfig: openMarketConfig,
+  });
+  const getTemplatePDA = (templateId: anchor.BN) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("template"), templateId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  // Template ids are handed out by the global counter
+  let standardTemplateId: anchor.BN;
+  let fastTemplateId: anchor.BN;
+  const createTemplate = async (params: ReturnType<typeof templateParams>) => {
+    const global = await program.account.globalState.fetch(globalState);
+    await program.methods
+      .createTemplate(params)
+      .accounts({
+        globalState: globalState,
+        template: getTemplatePDA(global.nextTemplateId),
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+    return global.nextTemplateId;
+  };
+
+  // Battles have to come from their arena's template; markets copy its fee
+  const useArenaTemplate = async (templateId: anchor.BN, tradeFeeBps = 0) => {
+    await program.methods
+      .updateArena(templateId, tradeFeeBps)
+      .accounts({
+        globalState: globalState,
+        template: getTemplatePDA(templateId),
+        arena: arena,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+  };
+
+  const getBattlePDA = (battleId: anchor.BN) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("battle"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  const getMarketPDA = (battleId: anchor.BN) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("market"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  const getPositionPDA = (battleId: anchor.BN, user: anchor.web3.PublicKey, creatureIndex: number) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("position"),
+        arena.toBuffer(),
+        battleId.toArrayLike(Buffer, "le", 8),
+        user.toBuffer(),
+        Buffer.from([creatureIndex]),
+      ],
+      program.programId
+    )[0];
+
+  const getLpPositionPDA = (battleId: anchor.BN, owner: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_position"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8), owner.toBuffer()],
+      program.programId
+    )[0];
+
+  // New battle on the standard template with a 1 SOL market
+  const openBattle = async (config: typeof openMarketConfig = openMarketConfig) => {
+    const battleId = await getNextBattleId();
+    await program.methods
+      .initializeBattle(standardTemplateId, null)
+      .accounts({
+        globalState: globalState,
+        arena: arena,
+        template: getTemplatePDA(standardTemplateId),
+        battleState: getBattlePDA(battleId),
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .initializeMarket(battleId, new anchor.BN(LAMPORTS_PER_SOL), config)
+      .accounts({
+        arena: arena,
+        template: getTemplatePDA(standardTemplateId),
+        marketState: getMarketPDA(battleId),
+        authority: provider.wallet.publicKey,
+        liquidityProvider: provider.wallet.publicKey,
+      })
+      .rpc();
+    return { battleId, battleState: getBattlePDA(battleId), marketState: getMarketPDA(battleId) };
+  };
+
+  const fundedKeypair = async (sol: number) => {
+    const keypair = anchor.web3.Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(keypair.publicKey, sol * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+    return keypair;
+  };
+
+  const placeBet = async (
+    battleId: anchor.BN,
+    creatureIndex: number,
+    lamports: number,
+    user?: anchor.web3.Keypair
+  ) => {
+    const owner = user ? user.publicKey : provider.wallet.publicKey;
+    await program.methods
+      .placeBet(creatureIndex, new anchor.BN(lamports))
+      .accounts({
+        marketState: getMarketPDA(battleId),
+        battleState: getBattlePDA(battleId),
+        userPosition: getPositionPDA(battleId, owner, creatureIndex),
+        user: owner,
+      })
+      .signers(user ? [user] : [])
+      .rpc();
+    return getPositionPDA(battleId, owner, creatureIndex);
+  };
+
+  // Just enough of the SPL token program to set up token markets, with the
+  // provider wallet as mint authority
+  const TOKEN_PROGRAM_ID = anchor.utils.token.TOKEN_PROGRAM_ID;
+  const createTokenProgramAccount = async (account: anchor.web3.PublicKey, space: number) =>
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: provider.wallet.publicKey,
+      newAccountPubkey: account,
+      lamports: await provider.connection.getMinimumBalanceForRentExemption(space),
+      space,
+      programId: TOKEN_PROGRAM_ID,
+    });
+  const createMint = async (decimals: number) => {
+    const mint = anchor.web3.Keypair.generate();
+    const initializeMint2 = new anchor.web3.TransactionInstruction({
+      programId: TOKEN_PROGRAM_ID,
+      keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+      data: Buffer.concat([Buffer.from([20, decimals]), provider.wallet.publicKey.toBuffer(), Buffer.from([0])]),
+    });
+    const tx = new anchor.web3.Transaction().add(await createTokenProgramAccount(mint.publicKey, 82), initializeMint2);
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+  };
+  const createTokenAccount = async (mint: anchor.web3.PublicKey, owner: anchor.web3.PublicKey, amount: number) => {
+    const account = anchor.web3.Keypair.generate();
+    const initializeAccount3 = new anchor.web3.TransactionInstruction({
+      programId: TOKEN_PROGRAM_ID,
+      keys: [
+        { pubkey: account.publicKey, isSigner: false, isWritable: true },
+        { pubkey: mint, isSigner: false, isWritable: false },
+      ],
+      data: Buffer.concat([Buffer.from([18]), owner.toBuffer()]),
+    });
+    const mintTo = new anchor.web3.TransactionInstruction({
+      programId: TOKEN_PROGRAM_ID,
+      keys: [
+        { pubkey: mint, isSigner: false, isWritable: true },
+        { pubkey: account.publicKey, isSigner: false, isWritable: true },
+        { pubkey: provider.wallet.publicKey, isSigner: true, isWritable: false },
+      ],
+      data: Buffer.concat([Buffer.from([7]), new anchor.BN(amount).toArrayLike(Buffer, "le", 8)]),
+    });
+    const tx = new anchor.web3.Transaction().add(await createTokenProgramAccount(account.publicKey, 165), initializeAccount3);
+    // Mints the program controls start out empty
+    if (amount > 0) tx.add(mintTo);
+    await provider.sendAndConfirm(tx, [account]);
+    return account.publicKey;
+  };
+  const tokenBalance = async (account: anchor.web3.PublicKey) =>
+    new anchor.BN((await provider.connection.getTokenAccountBalance(account)).value.amount);
+
+  // Battles can only be created by the global authority
+  before(async () => {
+    if (!(await provider.connection.getAccountInfo(globalState))) {
+      await program.methods
+        .initializeGlobal()
+        .accounts({
+          globalState: globalState,
+          authority: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    const [protocolConfig] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("protocol_config")],
+      program.programId
+    );
+    if (!(await provider.connection.getAccountInfo(protocolConfig))) {
+      await program.methods
+        .initializeConfig(defaultProtocolParams)
+        .accounts({
+          protocolConfig: protocolConfig,
+          authority: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    standardTemplateId = await createTemplate(templateParams(100, 10));
+    fastTemplateId = await createTemplate(templateParams(50, 1)); // Low HP, 1 second interval
+
+    await program.methods
+      .createArena(arenaName, standardTemplateId, null)
+      .accounts({
+        globalState: globalState,
+        template: getTemplatePDA(standardTemplateId),
+        arena: arena,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+  });
+
   // ============================================================================
   // TEST 1: Initialize Battle
   // ============================================================================
   it("✅ Initialize battle with 4 creatures", async () => {
+    sharedBattleId = await getNextBattleId();
     const battleId = sharedBattleId;
 
     const [battleState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("battle"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("battle"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -33,15 +290,13 @@ describe("Beast Index Arena - Complete Test Suite", () => {
 
     const tx = await program.methods
       .initializeBattle(
-        battleId,
-        100,  // hp
-        50,   // atk
-        20,   // def
-        30,   // spd
-        new anchor.BN(10),    // turn_interval (10 seconds)
-        new anchor.BN(86400)  // max_duration (24 hours)
+        standardTemplateId, // 100 HP, 10 second turns, 24 hour max duration
+        null                // start_time (start now)
       )
       .accounts({
+        globalState: globalState,
+        arena: arena,
+        template: getTemplatePDA(standardTemplateId),
         battleState: battleState,
         authority: provider.wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
@@ -62,8 +317,14 @@ describe("Beast Index Arena - Complete Test Suite", () => {
 
     // Verify
     if (battle.battleId.toNumber() !== battleId.toNumber()) throw new Error("❌ Battle ID wrong");
+    const arenaState = await program.account.arena.fetch(arena);
+    if (!battle.arena.equals(arena)) throw new Error("❌ Battle arena wrong");
+    if (!arenaState.currentBattleId.eq(battleId)) throw new Error("❌ Current battle not updated");
+    if (!arenaState.nextBattleId.eq(battleId.addn(1))) throw new Error("❌ Battle counter not advanced");
     if (battle.creatureHp[0] !== 100) throw new Error("❌ HP wrong");
     if (battle.creatureSpd[0] !== 30) throw new Error("❌ SPD wrong");
+    if (!battle.templateId.eq(standardTemplateId)) throw new Error("❌ Template ID wrong");
+    if (battle.templateVersion !== 1) throw new Error("❌ Template version wrong");
 
     console.log("\n✅ Battle initialization works!\n");
   });
@@ -75,7 +336,7 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const battleId = sharedBattleId;
 
     const [marketState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("market"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("market"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -84,10 +345,13 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const initialLiquidity = new anchor.BN(10 * LAMPORTS_PER_SOL); // 10 SOL liquidity for production
 
     const tx = await program.methods
-      .initializeMarket(battleId, initialLiquidity)
+      .initializeMarket(battleId, initialLiquidity, openMarketConfig)
       .accounts({
+        arena: arena,
+        template: getTemplatePDA(standardTemplateId),
         marketState: marketState,
         authority: provider.wallet.publicKey,
+        liquidityProvider: provider.wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .rpc();
@@ -117,12 +381,12 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const battleId = sharedBattleId;
 
     const [battleState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("battle"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("battle"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     const [marketState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("market"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("market"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -132,6 +396,7 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const [userPosition] = anchor.web3.PublicKey.findProgramAddressSync(
       [
         Buffer.from("position"),
+        arena.toBuffer(),
         battleId.toArrayLike(Buffer, "le", 8),
         provider.wallet.publicKey.toBuffer(),
         Buffer.from([creatureIndex])
@@ -191,12 +456,12 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const battleId = sharedBattleId;
 
     const [marketState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("market"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("market"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     const [battleState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("battle"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("battle"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -209,6 +474,7 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const [position1] = anchor.web3.PublicKey.findProgramAddressSync(
       [
         Buffer.from("position"),
+        arena.toBuffer(),
         battleId.toArrayLike(Buffer, "le", 8),
         provider.wallet.publicKey.toBuffer(),
         Buffer.from([creature1Index])
@@ -236,6 +502,7 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const [position2] = anchor.web3.PublicKey.findProgramAddressSync(
       [
         Buffer.from("position"),
+        arena.toBuffer(),
         battleId.toArrayLike(Buffer, "le", 8),
         provider.wallet.publicKey.toBuffer(),
         Buffer.from([creature2Index])
@@ -282,12 +549,12 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const battleId = sharedBattleId;
 
     const [battleState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("battle"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("battle"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     const [marketState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("market"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("market"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -296,6 +563,7 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const [userPosition] = anchor.web3.PublicKey.findProgramAddressSync(
       [
         Buffer.from("position"),
+        arena.toBuffer(),
         battleId.toArrayLike(Buffer, "le", 8),
         provider.wallet.publicKey.toBuffer(),
         Buffer.from([creatureIndex])
@@ -349,7 +617,7 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const battleId = sharedBattleId;
 
     const [battleState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("battle"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("battle"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
@@ -383,42 +651,42 @@ describe("Beast Index Arena - Complete Test Suite", () => {
   // TEST 7: Complete Battle & Claim Winnings
   // ============================================================================
   it("✅ Run battle to completion and claim winnings", async () => {
-    const battleId = getUniqueBattleId(); // New battle
+    const battleId = await getNextBattleId(); // New battle
 
     // Initialize battle
     const [battleState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("battle"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("battle"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
+    await useArenaTemplate(fastTemplateId);
     await program.methods
-      .initializeBattle(
-        battleId,
-        50,   // Low HP for faster battle
-        50,
-        20,
-        30,
-        new anchor.BN(1),     // 1 second interval
-        new anchor.BN(86400)
-      )
+      .initializeBattle(fastTemplateId, null) // Low HP for faster battle
       .accounts({
+        globalState: globalState,
+        arena: arena,
+        template: getTemplatePDA(fastTemplateId),
         battleState: battleState,
         authority: provider.wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .rpc();
+    await useArenaTemplate(standardTemplateId);
 
     // Initialize market
     const [marketState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("market"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("market"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     await program.methods
-      .initializeMarket(battleId, new anchor.BN(1000000))
+      .initializeMarket(battleId, new anchor.BN(1000000), openMarketConfig)
       .accounts({
+        arena: arena,
+        template: getTemplatePDA(fastTemplateId),
         marketState: marketState,
         authority: provider.wallet.publicKey,
+        liquidityProvider: provider.wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .rpc();
@@ -428,6 +696,7 @@ describe("Beast Index Arena - Complete Test Suite", () => {
       const [position] = anchor.web3.PublicKey.findProgramAddressSync(
         [
           Buffer.from("position"),
+          arena.toBuffer(),
           battleId.toArrayLike(Buffer, "le", 8),
           provider.wallet.publicKey.toBuffer(),
           Buffer.from([i])
@@ -468,6 +737,7 @@ describe("Beast Index Arena - Complete Test Suite", () => {
           const [winnerPosition] = anchor.web3.PublicKey.findProgramAddressSync(
             [
               Buffer.from("position"),
+              arena.toBuffer(),
               battleId.toArrayLike(Buffer, "le", 8),
               provider.wallet.publicKey.toBuffer(),
               Buffer.from([winnerIndex])
@@ -520,33 +790,29 @@ describe("Beast Index Arena - Complete Test Suite", () => {
   // TEST 8: Error Cases
   // ============================================================================
   it("✅ Error handling works", async () => {
-    const battleId = getUniqueBattleId();
+    const battleId = await getNextBattleId();
 
-    // Initialize battle
     const [battleState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("battle"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("battle"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
-    await program.methods
-      .initializeBattle(battleId, 100, 50, 20, 30, new anchor.BN(10), new anchor.BN(86400))
-      .accounts({
-        battleState: battleState,
-        authority: provider.wallet.publicKey,
-        systemProgram: anchor.web3.SystemProgram.programId,
-      })
-      .rpc();
-
     const [marketState] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("market"), battleId.toArrayLike(Buffer, "le", 8)],
+      [Buffer.from("market"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
+    // Battle and market are created together from the arena's rules
     await program.methods
-      .initializeMarket(battleId, new anchor.BN(1000000))
+      .createRound(new anchor.BN(1000000))
       .accounts({
+        globalState: globalState,
+        arena: arena,
+        template: getTemplatePDA(standardTemplateId),
+        battleState: battleState,
         marketState: marketState,
         authority: provider.wallet.publicKey,
+        liquidityProvider: provider.wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .rpc();
@@ -557,6 +823,7 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     const [position] = anchor.web3.PublicKey.findProgramAddressSync(
       [
         Buffer.from("position"),
+        arena.toBuffer(),
         battleId.toArrayLike(Buffer, "le", 8),
         provider.wallet.publicKey.toBuffer(),
         Buffer.from([0])
@@ -579,6 +846,1031 @@ describe("Beast Index Arena - Complete Test Suite", () => {
     console.log("\n✅ Error handling works!\n");
   });
 
+  // ============================================================================
+  // TEST 9: Roles
+  // ============================================================================
+  it("✅ Crank key can only execute turns", async () => {
+    const crank = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .grantRole(crank.publicKey, { crankOperator: {} })
+      .accounts({ globalState: globalState, authority: provider.wallet.publicKey })
+      .rpc();
+
+    let global = await program.account.globalState.fetch(globalState);
+    const member = global.roleMembers.find((m) => m.member.equals(crank.publicKey));
+    if (!member) throw new Error("❌ Role not granted");
+
+    // Not an admin, so it can't grant itself more roles
+    try {
+      await program.methods
+        .grantRole(crank.publicKey, { battleCreator: {} })
+        .accounts({ globalState: globalState, authority: crank.publicKey })
+        .signers([crank])
+        .rpc();
+      throw new Error("❌ Crank key granted itself a role");
+    } catch (err: any) {
+      if (!err.toString().includes("MissingRole")) throw err;
+    }
+
+    await program.methods
+      .revokeRole(crank.publicKey, { crankOperator: {} })
+      .accounts({ globalState: globalState, authority: provider.wallet.publicKey })
+      .rpc();
+
+    global = await program.account.globalState.fetch(globalState);
+    if (global.roleMembers.some((m) => m.member.equals(crank.publicKey))) {
+      throw new Error("❌ Role not revoked");
+    }
+
+    console.log("\n✅ Roles work!\n");
+  });
+
+  // ============================================================================
+  // TEST 10: Authority Transfer
+  // ============================================================================
+  it("✅ Authority transfer waits for the new key to accept", async () => {
+    const newAuthority = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .proposeAuthority(newAuthority.publicKey)
+      .accounts({ globalState: globalState, authority: provider.wallet.publicKey })
+      .rpc();
+
+    let global = await program.account.globalState.fetch(globalState);
+    if (!global.pendingAuthority.equals(newAuthority.publicKey)) throw new Error("❌ Not proposed");
+    if (!global.authority.equals(provider.wallet.publicKey)) throw new Error("❌ Authority changed early");
+
+    await program.methods
+      .cancelAuthorityProposal()
+      .accounts({ globalState: globalState, authority: provider.wallet.publicKey })
+      .rpc();
+
+    // A cancelled proposal can't be accepted
+    try {
+      await program.methods
+        .acceptAuthority()
+        .accounts({ globalState: globalState, newAuthority: newAuthority.publicKey })
+        .signers([newAuthority])
+        .rpc();
+      throw new Error("❌ Cancelled proposal was accepted");
+    } catch (err: any) {
+      if (!err.toString().includes("Unauthorized")) throw err;
+    }
+
+    global = await program.account.globalState.fetch(globalState);
+    if (!global.authority.equals(provider.wallet.publicKey)) throw new Error("❌ Authority changed");
+
+    console.log("\n✅ Authority transfer works!\n");
+  });
+
+  // ============================================================================
+  // TEST 11: Protocol Config
+  // ============================================================================
+  it("✅ Config updates bump the version", async () => {
+    const [protocolConfig] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("protocol_config")],
+      program.programId
+    );
+    const before = await program.account.protocolConfig.fetch(protocolConfig);
+
+    await program.methods
+      .updateConfig({ ...defaultProtocolParams, tradeFeeBps: 0 })
+      .accounts({ protocolConfig: protocolConfig, authority: provider.wallet.publicKey })
+      .rpc();
+
+    const after = await program.account.protocolConfig.fetch(protocolConfig);
+    if (after.version !== before.version + 1) throw new Error("❌ Version not bumped");
+
+    // A fee above the cap, or a zero price scale or claim window, is rejected
+    for (const invalid of [
+      { tradeFeeBps: 5_000 },
+      { priceScale: new anchor.BN(0) },
+      { defaultClaimWindowSecs: new anchor.BN(0) },
+    ]) {
+      try {
+        await program.methods
+          .updateConfig({ ...defaultProtocolParams, ...invalid })
+          .accounts({ protocolConfig: protocolConfig, authority: provider.wallet.publicKey })
+          .rpc();
+        throw new Error("❌ Invalid config accepted");
+      } catch (err: any) {
+        if (!err.toString().includes("InvalidProtocolConfig")) throw err;
+      }
+    }
+
+    console.log("\n✅ Protocol config works!\n");
+  });
+
+  // ============================================================================
+  // TEST 12: Circuit Breaker
+  // ============================================================================
+  it("✅ Global pause stops trading until lifted", async () => {
+    const battleId = sharedBattleId;
+
+    const [battleState] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("battle"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [marketState] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("market"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .setGlobalPause(true, false, false)
+      .accounts({ globalState: globalState, authority: provider.wallet.publicKey })
+      .rpc();
+
+    try {
+      await program.methods
+        .placeBet(0, new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+        .accounts({
+          marketState: marketState,
+          battleState: battleState,
+          user: provider.wallet.publicKey,
+        })
+        .rpc();
+      throw new Error("❌ Bet placed while trading was paused");
+    } catch (err: any) {
+      if (!err.toString().includes("TradingPaused")) throw err;
+    } finally {
+      await program.methods
+        .setGlobalPause(false, false, false)
+        .accounts({ globalState: globalState, authority: provider.wallet.publicKey })
+        .rpc();
+    }
+
+    console.log("\n✅ Circuit breaker works!\n");
+  });
+
+  // ============================================================================
+  // TEST 13: Battle Templates
+  // ============================================================================
+  it("✅ Battles record the template version they were created from", async () => {
+    const templateId = await createTemplate(templateParams(100, 10));
+    const template = getTemplatePDA(templateId);
+    await useArenaTemplate(templateId);
+
+    const createBattle = async () => {
+      const battleId = await getNextBattleId();
+      const [battleState] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("battle"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      await program.methods
+        .initializeBattle(templateId, null)
+        .accounts({
+          globalState: globalState,
+          arena: arena,
+          template: template,
+          battleState: battleState,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+      return battleState;
+    };
+
+    const before = await createBattle();
+
+    await program.methods
+      .updateTemplate({ ...templateParams(80, 10), tiebreak: { highestHp: {} } })
+      .accounts({ globalState: globalState, template: template, authority: provider.wallet.publicKey })
+      .rpc();
+    const after = await createBattle();
+
+    const oldBattle = await program.account.battleState.fetch(before);
+    const newBattle = await program.account.battleState.fetch(after);
+    if (oldBattle.templateVersion !== 1 || oldBattle.creatureHp[0] !== 100) {
+      throw new Error("❌ Existing battle changed with its template");
+    }
+    if (newBattle.templateVersion !== 2 || newBattle.creatureHp[0] !== 80) {
+      throw new Error("❌ New battle didn't use the updated template");
+    }
+    if (!("highestHp" in newBattle.tiebreak)) throw new Error("❌ Tiebreak not copied");
+    if (newBattle.abilityMultipliersBps[1] !== 15_000) throw new Error("❌ Default multipliers not applied");
+
+    await useArenaTemplate(standardTemplateId);
+
+    console.log("\n✅ Battle templates work!\n");
+  });
+
+  // ============================================================================
+  // TEST 14: Position Transfers
+  // ============================================================================
+  it("✅ Transfers move shares and respect the receiver's position limit", async () => {
+    const { battleId, battleState, marketState } = await openBattle({
+      ...openMarketConfig,
+      maxPosition: new anchor.BN(50_000_000),
+    });
+    const receiver = await fundedKeypair(1);
+
+    // ~38.5M shares for the receiver, ~19.5M for the sender
+    const receiverPosition = await placeBet(battleId, 0, 40_000_000, receiver);
+    const senderPosition = await placeBet(battleId, 0, 100_000_000);
+    const sent = await program.account.userPosition.fetch(senderPosition);
+    const received = await program.account.userPosition.fetch(receiverPosition);
+
+    const transfer = (shares: number) =>
+      program.methods
+        .transferPosition(new anchor.BN(shares), receiver.publicKey)
+        .accounts({
+          battleState: battleState,
+          marketState: marketState,
+          userPosition: senderPosition,
+          recipientPosition: receiverPosition,
+          user: provider.wallet.publicKey,
+        })
+        .rpc();
+
+    // Would push the receiver over max_position
+    try {
+      await transfer(15_000_000);
+      throw new Error("❌ Transfer exceeded the receiver's position limit");
+    } catch (err: any) {
+      if (!err.toString().includes("PositionLimitExceeded")) throw err;
+    }
+
+    await transfer(5_000_000);
+    const senderAfter = await program.account.userPosition.fetch(senderPosition);
+    const receiverAfter = await program.account.userPosition.fetch(receiverPosition);
+    if (!senderAfter.amount.eq(sent.amount.subn(5_000_000))) throw new Error("❌ Sender not debited");
+    if (!receiverAfter.amount.eq(received.amount.addn(5_000_000))) throw new Error("❌ Receiver not credited");
+
+    console.log("\n✅ Position transfers work!\n");
+  });
+
+  // ============================================================================
+  // TEST 15: Refunds
+  // ============================================================================
+  it("✅ Cancelled battles refund what each position paid", async () => {
+    const { battleId, battleState, marketState } = await openBattle();
+    const seller = await fundedKeypair(1);
+
+    const holderPosition = await placeBet(battleId, 0, 100_000_000);
+    const sellerPosition = await placeBet(battleId, 1, 100_000_000, seller);
+
+    // Selling half takes half the cost basis with it
+    const bought = await program.account.userPosition.fetch(sellerPosition);
+    await program.methods
+      .sellShares(bought.amount.divn(2))
+      .accounts({
+        battleState: battleState,
+        marketState: marketState,
+        userPosition: sellerPosition,
+        user: seller.publicKey,
+      })
+      .signers([seller])
+      .rpc();
+    const sold = await program.account.userPosition.fetch(sellerPosition);
+    if (!sold.costBasis.eq(new anchor.BN(50_000_000))) throw new Error("❌ Cost basis not reduced on sell");
+
+    await program.methods
+      .cancelBattle()
+      .accounts({
+        globalState: globalState,
+        battleState: battleState,
+        marketState: marketState,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    const claimRefund = (position: anchor.web3.PublicKey, user?: anchor.web3.Keypair) =>
+      program.methods
+        .claimRefund()
+        .accounts({
+          battleState: battleState,
+          marketState: marketState,
+          userPosition: position,
+          user: user ? user.publicKey : provider.wallet.publicKey,
+        })
+        .signers(user ? [user] : [])
+        .rpc();
+
+    // The holder gets exactly the 0.1 SOL that went into the pool
+    let market = await program.account.marketState.fetch(marketState);
+    await claimRefund(holderPosition);
+    let after = await program.account.marketState.fetch(marketState);
+    if (market.creature0Pool.sub(after.creature0Pool).toNumber() !== 100_000_000) {
+      throw new Error("❌ Holder not refunded their cost");
+    }
+
+    // The seller gets their remaining basis, capped at what is left in the pool
+    market = after;
+    const expected = anchor.BN.min(sold.costBasis, market.creature1Pool);
+    const balanceBefore = await provider.connection.getBalance(seller.publicKey);
+    await claimRefund(sellerPosition, seller);
+    const balanceAfter = await provider.connection.getBalance(seller.publicKey);
+    if (balanceAfter - balanceBefore !== expected.toNumber()) throw new Error("❌ Seller refund wrong");
+
+    try {
+      await claimRefund(holderPosition);
+      throw new Error("❌ Refund claimed twice");
+    } catch (err: any) {
+      if (!err.toString().includes("AlreadyClaimed")) throw err;
+    }
+
+    console.log("\n✅ Refunds work!\n");
+  });
+
+  // ============================================================================
+  // TEST 16: Liquidity Providers
+  // ============================================================================
+  it("✅ LPs buy in before any bets and can't withdraw bettors' stakes", async () => {
+    // A 1% fee leaves the LPs something to split beyond the stakes
+    await useArenaTemplate(standardTemplateId, 100);
+    const { battleId, battleState, marketState } = await openBattle();
+    await useArenaTemplate(standardTemplateId);
+    const provider2 = await fundedKeypair(2);
+
+    const marketRent = await provider.connection.getMinimumBalanceForRentExemption(
+      (await provider.connection.getAccountInfo(marketState))!.data.length
+    );
+    const available = async () => (await provider.connection.getBalance(marketState)) - marketRent;
+
+    // Before any bets the LPs' side is just the 1 SOL deposit
+    let market = await program.account.marketState.fetch(marketState);
+    const lpValue = new anchor.BN(await available()).sub(market.totalPool);
+    const deposit = new anchor.BN(LAMPORTS_PER_SOL);
+    const provider2Lp = getLpPositionPDA(battleId, provider2.publicKey);
+    const addLiquidity = () =>
+      program.methods
+        .addLiquidity(deposit)
+        .accounts({
+          battleState: battleState,
+          marketState: marketState,
+          lpPosition: provider2Lp,
+          provider: provider2.publicKey,
+        })
+        .signers([provider2])
+        .rpc();
+    await addLiquidity();
+    const lp = await program.account.lpPosition.fetch(provider2Lp);
+    if (!lp.shares.eq(deposit.mul(market.totalLpShares).div(lpValue))) {
+      throw new Error("❌ LP shares not priced at pool value");
+    }
+
+    // Once a bet is open, a deposit would reprice it
+    const bettorPosition = await placeBet(battleId, 0, 100_000_000);
+    try {
+      await addLiquidity();
+      throw new Error("❌ Liquidity added after a bet");
+    } catch (err: any) {
+      if (!err.toString().includes("LiquidityWindowClosed")) throw err;
+    }
+
+    await program.methods
+      .cancelBattle()
+      .accounts({
+        globalState: globalState,
+        battleState: battleState,
+        marketState: marketState,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    // Each LP gets its cut of everything beyond the refundable stakes
+    const withdraw = async (owner?: anchor.web3.Keypair) => {
+      const ownerKey = owner ? owner.publicKey : provider.wallet.publicKey;
+      const lpPosition = getLpPositionPDA(battleId, ownerKey);
+      const shares = (await program.account.lpPosition.fetch(lpPosition)).shares;
+      market = await program.account.marketState.fetch(marketState);
+      const before = await available();
+      const expected = new anchor.BN(before).sub(market.totalPool).mul(shares).div(market.totalLpShares);
+      await program.methods
+        .lpWithdraw()
+        .accounts({
+          battleState: battleState,
+          marketState: marketState,
+          lpPosition: lpPosition,
+          owner: ownerKey,
+        })
+        .signers(owner ? [owner] : [])
+        .rpc();
+      if (before - (await available()) !== expected.toNumber()) throw new Error("❌ LP payout wrong");
+    };
+    await withdraw(provider2);
+    await withdraw();
+
+    // The bettor's stake is still there to refund
+    await program.methods
+      .claimRefund()
+      .accounts({
+        battleState: battleState,
+        marketState: marketState,
+        userPosition: bettorPosition,
+        user: provider.wallet.publicKey,
+      })
+      .rpc();
+    const refunded = await program.account.userPosition.fetch(bettorPosition);
+    if (!refunded.claimed) throw new Error("❌ Bettor could not be refunded after LPs withdrew");
+
+    console.log("\n✅ Liquidity providers work!\n");
+  });
+
+  // ============================================================================
+  // TEST 17: House Vault
+  // ============================================================================
+  it("✅ Vault shares only move while none of its capital is deployed", async () => {
+    const [houseVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("house_vault")],
+      program.programId
+    );
+    if (!(await provider.connection.getAccountInfo(houseVault))) {
+      await program.methods
+        .initializeHouseVault()
+        .accounts({ houseVault: houseVault, authority: provider.wallet.publicKey })
+        .rpc();
+    }
+
+    const depositor = await fundedKeypair(3);
+    const [vaultPosition] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_position"), depositor.publicKey.toBuffer()],
+      program.programId
+    );
+    const assetsBefore = (await program.account.houseVault.fetch(houseVault)).totalAssets;
+    await program.methods
+      .depositToVault(new anchor.BN(2 * LAMPORTS_PER_SOL), new anchor.BN(1))
+      .accounts({ houseVault: houseVault, vaultPosition: vaultPosition, owner: depositor.publicKey })
+      .signers([depositor])
+      .rpc();
+
+    // Lamports sent straight to the vault don't count towards its value
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: provider.wallet.publicKey,
+          toPubkey: houseVault,
+          lamports: LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    const assets = (await program.account.houseVault.fetch(houseVault)).totalAssets;
+    if (!assets.sub(assetsBefore).eq(new anchor.BN(2 * LAMPORTS_PER_SOL))) {
+      throw new Error("❌ Vault assets moved by a direct transfer");
+    }
+
+    const { battleId, battleState, marketState } = await openBattle();
+    const vaultLpPosition = getLpPositionPDA(battleId, houseVault);
+    const deploy = (authority: anchor.web3.Keypair | null) =>
+      program.methods
+        .vaultAddLiquidity(new anchor.BN(LAMPORTS_PER_SOL))
+        .accounts({
+          houseVault: houseVault,
+          battleState: battleState,
+          marketState: marketState,
+          vaultLpPosition: vaultLpPosition,
+          authority: authority ? authority.publicKey : provider.wallet.publicKey,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc();
+
+    // Only treasury managers deploy vault capital
+    try {
+      await deploy(await fundedKeypair(1));
+      throw new Error("❌ Vault deployed by a key without the role");
+    } catch (err: any) {
+      if (!err.toString().includes("MissingRole")) throw err;
+    }
+    await deploy(null);
+
+    const shares = (await program.account.vaultPosition.fetch(vaultPosition)).shares;
+    const withdraw = () =>
+      program.methods
+        .withdrawFromVault(shares)
+        .accounts({ houseVault: houseVault, vaultPosition: vaultPosition, owner: depositor.publicKey })
+        .signers([depositor])
+        .rpc();
+    try {
+      await withdraw();
+      throw new Error("❌ Vault withdrawal priced while capital was deployed");
+    } catch (err: any) {
+      if (!err.toString().includes("VaultCapitalDeployed")) throw err;
+    }
+
+    await program.methods
+      .cancelBattle()
+      .accounts({
+        globalState: globalState,
+        battleState: battleState,
+        marketState: marketState,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .vaultLpWithdraw()
+      .accounts({
+        houseVault: houseVault,
+        battleState: battleState,
+        marketState: marketState,
+        vaultLpPosition: vaultLpPosition,
+      })
+      .rpc();
+    const vault = await program.account.houseVault.fetch(houseVault);
+    if (!vault.deployed.isZero()) throw new Error("❌ Vault capital still marked as deployed");
+
+    // Back in the vault, the shares redeem for their cut of what it holds
+    const expected = shares.mul(vault.totalAssets).div(vault.totalShares);
+    const before = await provider.connection.getBalance(depositor.publicKey);
+    await withdraw();
+    const after = await provider.connection.getBalance(depositor.publicKey);
+    if (after - before !== expected.toNumber()) throw new Error("❌ Vault payout wrong");
+
+    console.log("\n✅ House vault works!\n");
+  });
+
+  // ============================================================================
+  // TEST 18: Closing Accounts
+  // ============================================================================
+  it("✅ Emptied accounts close and hand their rent back", async () => {
+    const { battleId, battleState, marketState } = await openBattle();
+    const owner = await fundedKeypair(2);
+    const lpPosition = getLpPositionPDA(battleId, owner.publicKey);
+    await program.methods
+      .addLiquidity(new anchor.BN(LAMPORTS_PER_SOL))
+      .accounts({
+        battleState: battleState,
+        marketState: marketState,
+        lpPosition: lpPosition,
+        provider: owner.publicKey,
+      })
+      .signers([owner])
+      .rpc();
+    // The position records its arena, so closing it needs nothing else
+    const lp = await program.account.lpPosition.fetch(lpPosition);
+    if (!lp.arena.equals(arena)) throw new Error("❌ LP position arena not recorded");
+
+    // The provider wallet pays the fees, so the owner gains exactly the rent
+    const closeLp = () =>
+      program.methods
+        .closeLpPosition()
+        .accounts({ lpPosition: lpPosition, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+    try {
+      await closeLp();
+      throw new Error("❌ LP position closed with shares outstanding");
+    } catch (err: any) {
+      if (!err.toString().includes("LpSharesOutstanding")) throw err;
+    }
+
+    await program.methods
+      .cancelBattle()
+      .accounts({
+        globalState: globalState,
+        battleState: battleState,
+        marketState: marketState,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .lpWithdraw()
+      .accounts({
+        battleState: battleState,
+        marketState: marketState,
+        lpPosition: lpPosition,
+        owner: owner.publicKey,
+      })
+      .signers([owner])
+      .rpc();
+
+    let rent = await provider.connection.getBalance(lpPosition);
+    let before = await provider.connection.getBalance(owner.publicKey);
+    await closeLp();
+    if ((await provider.connection.getAccountInfo(lpPosition)) !== null) {
+      throw new Error("❌ LP position still open");
+    }
+    if ((await provider.connection.getBalance(owner.publicKey)) - before !== rent) {
+      throw new Error("❌ LP position rent not returned");
+    }
+
+    // Same for a vault position once its shares are withdrawn
+    const [houseVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("house_vault")],
+      program.programId
+    );
+    const [vaultPosition] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_position"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .depositToVault(new anchor.BN(LAMPORTS_PER_SOL / 10), new anchor.BN(1))
+      .accounts({ houseVault: houseVault, vaultPosition: vaultPosition, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    const shares = (await program.account.vaultPosition.fetch(vaultPosition)).shares;
+    await program.methods
+      .withdrawFromVault(shares)
+      .accounts({ houseVault: houseVault, vaultPosition: vaultPosition, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    rent = await provider.connection.getBalance(vaultPosition);
+    before = await provider.connection.getBalance(owner.publicKey);
+    await program.methods
+      .closeVaultPosition()
+      .accounts({ vaultPosition: vaultPosition, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    if ((await provider.connection.getBalance(owner.publicKey)) - before !== rent) {
+      throw new Error("❌ Vault position rent not returned");
+    }
+
+    console.log("\n✅ Closing accounts works!\n");
+  });
+
+  // ============================================================================
+  // TEST 19: Scheduled Start
+  // ============================================================================
+  it("✅ Scheduled battles take bets but no turns before they start", async () => {
+    const battleId = await getNextBattleId();
+    const battleState = getBattlePDA(battleId);
+    const marketState = getMarketPDA(battleId);
+    const now = (await provider.connection.getBlockTime(await provider.connection.getSlot()))!;
+    const initialize = (startTime: number) =>
+      program.methods
+        .initializeBattle(standardTemplateId, new anchor.BN(startTime))
+        .accounts({
+          globalState: globalState,
+          arena: arena,
+          template: getTemplatePDA(standardTemplateId),
+          battleState: battleState,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+    try {
+      await initialize(now - 3600);
+      throw new Error("❌ Battle scheduled in the past");
+    } catch (err: any) {
+      if (!err.toString().includes("InvalidStartTime")) throw err;
+    }
+
+    const startTime = now + 600;
+    await initialize(startTime);
+    const battle = await program.account.battleState.fetch(battleState);
+    if (battle.startTime.toNumber() !== startTime || battle.lastTurnTime.toNumber() !== startTime) {
+      throw new Error("❌ Start time not recorded");
+    }
+
+    // The betting window is open before the first turn
+    await program.methods
+      .initializeMarket(battleId, new anchor.BN(LAMPORTS_PER_SOL), openMarketConfig)
+      .accounts({
+        arena: arena,
+        template: getTemplatePDA(standardTemplateId),
+        marketState: marketState,
+        authority: provider.wallet.publicKey,
+        liquidityProvider: provider.wallet.publicKey,
+      })
+      .rpc();
+    const position = await placeBet(battleId, 0, 100_000_000);
+    if ((await program.account.userPosition.fetch(position)).amount.isZero()) {
+      throw new Error("❌ Bet not placed in the betting window");
+    }
+
+    try {
+      await program.methods
+        .executeTurn()
+        .accounts({
+          battleState: battleState,
+          executer: provider.wallet.publicKey,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        })
+        .rpc();
+      throw new Error("❌ Turn ran before the start time");
+    } catch (err: any) {
+      if (!err.toString().includes("BattleNotStarted")) throw err;
+    }
+
+    console.log("\n✅ Scheduled starts work!\n");
+  });
+
+  // ============================================================================
+  // TEST 20: Lock Rules
+  // ============================================================================
+  it("✅ Markets lock bets and sells once the battle passes their cutoff", async () => {
+    const { battleId, battleState, marketState } = await openBattle({
+      ...openMarketConfig,
+      lockAfterTurn: new anchor.BN(1),
+      allowSellsWhenLocked: false,
+    });
+    const position = await placeBet(battleId, 0, 100_000_000);
+
+    const lockStatus = () =>
+      program.methods.getLockStatus().accounts({ battleState: battleState, marketState: marketState }).view();
+    if ((await lockStatus()).betsLocked) throw new Error("❌ Market locked before its cutoff");
+
+    await new Promise((resolve) => setTimeout(resolve, 11000));
+    await program.methods
+      .executeTurn()
+      .accounts({
+        battleState: battleState,
+        executer: provider.wallet.publicKey,
+        clock: SYSVAR_CLOCK_PUBKEY,
+      })
+      .rpc();
+
+    const status = await lockStatus();
+    if (!status.betsLocked || !status.sellsLocked || status.reason.turnLimit === undefined) {
+      throw new Error("❌ Lock status wrong after the cutoff turn");
+    }
+    try {
+      await placeBet(battleId, 1, 100_000_000);
+      throw new Error("❌ Bet placed after the cutoff");
+    } catch (err: any) {
+      if (!err.toString().includes("BetsLocked")) throw err;
+    }
+    try {
+      await program.methods
+        .sellShares((await program.account.userPosition.fetch(position)).amount)
+        .accounts({
+          battleState: battleState,
+          marketState: marketState,
+          userPosition: position,
+          user: provider.wallet.publicKey,
+        })
+        .rpc();
+      throw new Error("❌ Sold after the cutoff");
+    } catch (err: any) {
+      if (!err.toString().includes("SellsLocked")) throw err;
+    }
+
+    console.log("\n✅ Lock rules work!\n");
+  });
+
+  // ============================================================================
+  // TEST 21: Token Markets
+  // ============================================================================
+  it("✅ Token markets take bets and pay out in their mint", async () => {
+    const mint = await createMint(6);
+    const battleId = await getNextBattleId();
+    const battleState = getBattlePDA(battleId);
+    const marketState = getMarketPDA(battleId);
+    const [marketVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("market_vault"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .initializeBattle(standardTemplateId, null)
+      .accounts({
+        globalState: globalState,
+        arena: arena,
+        template: getTemplatePDA(standardTemplateId),
+        battleState: battleState,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    const providerTokens = await createTokenAccount(mint, provider.wallet.publicKey, 1_000_000_000);
+    await program.methods
+      .initializeTokenMarket(battleId, new anchor.BN(1_000_000_000), null)
+      .accounts({
+        globalState: globalState,
+        arena: arena,
+        battleState: battleState,
+        template: getTemplatePDA(standardTemplateId),
+        marketState: marketState,
+        lpPosition: getLpPositionPDA(battleId, provider.wallet.publicKey),
+        tokenMint: mint,
+        marketVault: marketVault,
+        providerTokenAccount: providerTokens,
+        authority: provider.wallet.publicKey,
+        liquidityProvider: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    if (!(await tokenBalance(marketVault)).eqn(1_000_000_000)) throw new Error("❌ Liquidity not in the vault");
+
+    // Lamport bets are refused
+    const bettor = await fundedKeypair(1);
+    try {
+      await placeBet(battleId, 0, 100_000_000, bettor);
+      throw new Error("❌ Token market took lamports");
+    } catch (err: any) {
+      if (!err.toString().includes("MissingTokenAccounts")) throw err;
+    }
+
+    const bettorTokens = await createTokenAccount(mint, bettor.publicKey, 100_000_000);
+    const tokenAccounts = {
+      tokenMint: mint,
+      userTokenAccount: bettorTokens,
+      marketVault: marketVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const position = getPositionPDA(battleId, bettor.publicKey, 0);
+    await program.methods
+      .placeBet(0, new anchor.BN(100_000_000))
+      .accounts({
+        marketState: marketState,
+        battleState: battleState,
+        userPosition: position,
+        user: bettor.publicKey,
+        ...tokenAccounts,
+      })
+      .signers([bettor])
+      .rpc();
+    if (!(await tokenBalance(bettorTokens)).isZero() || !(await tokenBalance(marketVault)).eqn(1_100_000_000)) {
+      throw new Error("❌ Bet not paid in tokens");
+    }
+
+    // Refunds and LP withdrawals come back out of the vault
+    await program.methods
+      .cancelBattle()
+      .accounts({
+        globalState: globalState,
+        battleState: battleState,
+        marketState: marketState,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .claimRefund()
+      .accounts({
+        battleState: battleState,
+        marketState: marketState,
+        userPosition: position,
+        user: bettor.publicKey,
+        ...tokenAccounts,
+      })
+      .signers([bettor])
+      .rpc();
+    if (!(await tokenBalance(bettorTokens)).eqn(100_000_000)) throw new Error("❌ Token refund wrong");
+
+    await program.methods
+      .lpWithdraw()
+      .accounts({
+        battleState: battleState,
+        marketState: marketState,
+        lpPosition: getLpPositionPDA(battleId, provider.wallet.publicKey),
+        tokenMint: mint,
+        ownerTokenAccount: providerTokens,
+        marketVault: marketVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        owner: provider.wallet.publicKey,
+      })
+      .rpc();
+    if (!(await tokenBalance(providerTokens)).eqn(1_000_000_000) || !(await tokenBalance(marketVault)).isZero()) {
+      throw new Error("❌ LP not paid out in tokens");
+    }
+
+    console.log("\n✅ Token markets work!\n");
+  });
+
+  // ============================================================================
+  // TEST 22: Outcome Tokens
+  // ============================================================================
+  it("✅ Outcome tokens are minted on buys, burned on sells and redeem for refunds", async () => {
+    const { battleId, battleState, marketState } = await openBattle();
+    const outcomeMints = [0, 1, 2, 3].map(
+      (i) =>
+        anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("outcome_mint"), arena.toBuffer(), battleId.toArrayLike(Buffer, "le", 8), Buffer.from([i])],
+          program.programId
+        )[0]
+    );
+    await program.methods
+      .initializeOutcomeMints(9)
+      .accounts({
+        globalState: globalState,
+        battleState: battleState,
+        marketState: marketState,
+        outcomeMint0: outcomeMints[0],
+        outcomeMint1: outcomeMints[1],
+        outcomeMint2: outcomeMints[2],
+        outcomeMint3: outcomeMints[3],
+        authority: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const holder = await fundedKeypair(2);
+    const holderTokens = await createTokenAccount(outcomeMints[0], holder.publicKey, 0);
+    const outcomeAccounts = {
+      battleState: battleState,
+      marketState: marketState,
+      outcomeMint: outcomeMints[0],
+      userOutcomeAccount: holderTokens,
+      outcomeTokenProgram: TOKEN_PROGRAM_ID,
+      user: holder.publicKey,
+    };
+
+    // Positions aren't booked on a tokenized market
+    try {
+      await placeBet(battleId, 0, 100_000_000, holder);
+      throw new Error("❌ Position bet on a tokenized market");
+    } catch (err: any) {
+      if (!err.toString().includes("SharesAreTokenized")) throw err;
+    }
+
+    await program.methods
+      .buyOutcomeTokens(0, new anchor.BN(200_000_000))
+      .accounts(outcomeAccounts)
+      .signers([holder])
+      .rpc();
+    let market = await program.account.marketState.fetch(marketState);
+    const minted = await tokenBalance(holderTokens);
+    if (!minted.eq(market.initialLiquidity.sub(market.creature0Shares))) {
+      throw new Error("❌ Minted tokens don't match the shares bought");
+    }
+
+    const sold = minted.divn(2);
+    let before = await provider.connection.getBalance(holder.publicKey);
+    await program.methods
+      .sellOutcomeTokens(0, sold)
+      .accounts(outcomeAccounts)
+      .signers([holder])
+      .rpc();
+    const afterSell = await program.account.marketState.fetch(marketState);
+    const proceeds = market.creature0Pool.sub(afterSell.creature0Pool);
+    if (!(await tokenBalance(holderTokens)).eq(minted.sub(sold))) throw new Error("❌ Sold tokens not burned");
+    if ((await provider.connection.getBalance(holder.publicKey)) - before !== proceeds.toNumber()) {
+      throw new Error("❌ Sale proceeds wrong");
+    }
+
+    await program.methods
+      .cancelBattle()
+      .accounts({
+        globalState: globalState,
+        battleState: battleState,
+        marketState: marketState,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    // The last holder out gets the rest of the pool
+    market = afterSell;
+    before = await provider.connection.getBalance(holder.publicKey);
+    await program.methods
+      .redeemOutcomeTokens(0)
+      .accounts(outcomeAccounts)
+      .signers([holder])
+      .rpc();
+    if (!(await tokenBalance(holderTokens)).isZero()) throw new Error("❌ Redeemed tokens not burned");
+    if ((await provider.connection.getBalance(holder.publicKey)) - before !== market.creature0Pool.toNumber()) {
+      throw new Error("❌ Outcome token refund wrong");
+    }
+
+    console.log("\n✅ Outcome tokens work!\n");
+  });
+
+  // ============================================================================
+  // TEST 23: Swapping Positions
+  // ============================================================================
+  it("✅ Swaps move a position between creatures without leaving the market", async () => {
+    const { battleId, battleState, marketState } = await openBattle();
+    const trader = await fundedKeypair(1);
+    const fromPosition = await placeBet(battleId, 0, 300_000_000, trader);
+    const toPosition = getPositionPDA(battleId, trader.publicKey, 1);
+    const shares = (await program.account.userPosition.fetch(fromPosition)).amount;
+
+    const swap = (toCreature: number, minOut: anchor.BN) =>
+      program.methods
+        .swapPosition(0, toCreature, shares, minOut)
+        .accounts({
+          battleState: battleState,
+          marketState: marketState,
+          fromPosition: fromPosition,
+          toPosition: getPositionPDA(battleId, trader.publicKey, toCreature),
+          user: trader.publicKey,
+        })
+        .signers([trader])
+        .rpc();
+    try {
+      await swap(0, new anchor.BN(0));
+      throw new Error("❌ Swapped a creature into itself");
+    } catch (err: any) {
+      if (!err.toString().includes("SameCreature")) throw err;
+    }
+    try {
+      await swap(1, shares.muln(10));
+      throw new Error("❌ Swap ignored min_out");
+    } catch (err: any) {
+      if (!err.toString().includes("SlippageExceeded")) throw err;
+    }
+
+    const marketBefore = await program.account.marketState.fetch(marketState);
+    const lamportsBefore = await provider.connection.getBalance(trader.publicKey);
+    await swap(1, new anchor.BN(1));
+    const market = await program.account.marketState.fetch(marketState);
+    const from = await program.account.userPosition.fetch(fromPosition);
+    const to = await program.account.userPosition.fetch(toPosition);
+
+    // The sale proceeds are the new position's stake; only its rent is paid in
+    const proceeds = marketBefore.creature0Pool.sub(market.creature0Pool);
+    if (!from.amount.isZero() || to.amount.isZero()) throw new Error("❌ Shares not moved");
+    if (!market.creature1Pool.eq(proceeds) || !to.costBasis.eq(proceeds)) {
+      throw new Error("❌ Proceeds not reinvested");
+    }
+    if (!market.totalPool.eq(marketBefore.totalPool)) throw new Error("❌ Swap changed the total pool");
+    const spent = lamportsBefore - (await provider.connection.getBalance(trader.publicKey));
+    if (spent !== (await provider.connection.getBalance(toPosition))) {
+      throw new Error("❌ Swap moved more than the new position's rent");
+    }
+
+    console.log("\n✅ Position swaps work!\n");
+  });
+
   // ============================================================================
   // FINAL SUMMARY
   // ============================================================================